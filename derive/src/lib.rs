@@ -0,0 +1,287 @@
+//! Derive macros for `bevy_file_dialog`, use them through the re-exports in
+//! `bevy_file_dialog`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{parenthesized, parse_macro_input, DeriveInput, LitStr, Token};
+
+/// Derive `DialogKind` from `#[dialog(...)]` attributes.
+///
+/// Supported attributes:
+/// - `title = "..."`, `file_name = "..."` and `directory = "..."` set the
+///   defaults of the dialog
+/// - `filter("Name", "ext", ...)` adds named filter, it can be repeated
+/// - `save`, `load`, `pick_file` and `pick_directory` register the marker
+///   with the corresponding `FileDialogPlugin::with_*` function when
+///   `FileDialogPlugin::with_dialog_kind` is called
+#[proc_macro_derive(FileDialogKind, attributes(dialog))]
+pub fn derive_file_dialog_kind(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct DialogAttributes {
+    title: Option<LitStr>,
+    file_name: Option<LitStr>,
+    directory: Option<LitStr>,
+    filters: Vec<(LitStr, Vec<LitStr>)>,
+    register: Vec<syn::Ident>,
+}
+
+fn parse_attributes(input: &DeriveInput) -> syn::Result<DialogAttributes> {
+    let mut attributes = DialogAttributes::default();
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("dialog"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("title") {
+                attributes.title = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("file_name") {
+                attributes.file_name = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("directory") {
+                attributes.directory = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("filter") {
+                let content;
+                parenthesized!(content in meta.input);
+                let mut values =
+                    Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?.into_iter();
+
+                let Some(name) = values.next() else {
+                    return Err(meta.error("expected filter name and extensions"));
+                };
+
+                let extensions: Vec<_> = values.collect();
+                if extensions.is_empty() {
+                    return Err(meta.error("expected at least one extension"));
+                }
+
+                attributes.filters.push((name, extensions));
+            } else if let Some(register) = register_function(&meta.path) {
+                attributes.register.push(register);
+            } else {
+                return Err(meta.error(
+                    "expected `title`, `file_name`, `directory`, `filter`, `save`, `load`, \
+                     `pick_file` or `pick_directory`",
+                ));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(attributes)
+}
+
+fn register_function(path: &syn::Path) -> Option<syn::Ident> {
+    let name = match path.get_ident()?.to_string().as_str() {
+        "save" => "with_save_file",
+        "load" => "with_load_file",
+        "pick_file" => "with_pick_file",
+        "pick_directory" => "with_pick_directory",
+        _ => return None,
+    };
+
+    Some(syn::Ident::new(name, path.get_ident()?.span()))
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let attributes = parse_attributes(&input)?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let optional_string = |value: &Option<LitStr>| match value {
+        Some(value) => quote!(::core::option::Option::Some(::std::string::String::from(#value))),
+        None => quote!(::core::option::Option::None),
+    };
+
+    let title = optional_string(&attributes.title);
+    let file_name = optional_string(&attributes.file_name);
+    let directory = match &attributes.directory {
+        Some(directory) => {
+            quote!(::core::option::Option::Some(::std::path::PathBuf::from(#directory)))
+        }
+        None => quote!(::core::option::Option::None),
+    };
+
+    let filters = attributes.filters.iter().map(|(name, extensions)| {
+        quote! {
+            (
+                ::std::string::String::from(#name),
+                ::std::vec![#(::std::string::String::from(#extensions)),*],
+            )
+        }
+    });
+
+    let register = &attributes.register;
+
+    Ok(quote! {
+        impl #impl_generics ::bevy_file_dialog::DialogKind for #name #ty_generics #where_clause {
+            fn title() -> ::core::option::Option<::std::string::String> {
+                #title
+            }
+
+            fn filters() -> ::std::vec::Vec<(
+                ::std::string::String,
+                ::std::vec::Vec<::std::string::String>,
+            )> {
+                ::std::vec![#(#filters),*]
+            }
+
+            fn file_name() -> ::core::option::Option<::std::string::String> {
+                #file_name
+            }
+
+            fn directory() -> ::core::option::Option<::std::path::PathBuf> {
+                #directory
+            }
+
+            fn register(
+                plugin: ::bevy_file_dialog::FileDialogPlugin,
+            ) -> ::bevy_file_dialog::FileDialogPlugin {
+                plugin #(.#register::<Self>())*
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn expand_error(input: DeriveInput) -> String {
+        expand(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn all_attributes() {
+        let input: DeriveInput = parse_quote! {
+            #[dialog(title = "Open Level", file_name = "level.lvl", directory = "levels")]
+            #[dialog(filter("Level", "lvl", "ron"), filter("Map", "map"))]
+            #[dialog(save, load, pick_file, pick_directory)]
+            struct LevelFile;
+        };
+
+        let attributes = parse_attributes(&input).unwrap();
+        assert_eq!(attributes.title.unwrap().value(), "Open Level");
+        assert_eq!(attributes.file_name.unwrap().value(), "level.lvl");
+        assert_eq!(attributes.directory.unwrap().value(), "levels");
+
+        let filters: Vec<_> = attributes
+            .filters
+            .iter()
+            .map(|(name, extensions)| {
+                let extensions: Vec<_> = extensions.iter().map(LitStr::value).collect();
+                (name.value(), extensions)
+            })
+            .collect();
+        assert_eq!(
+            filters,
+            [
+                (
+                    "Level".to_string(),
+                    vec!["lvl".to_string(), "ron".to_string()]
+                ),
+                ("Map".to_string(), vec!["map".to_string()]),
+            ]
+        );
+
+        let register: Vec<_> = attributes
+            .register
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            register,
+            [
+                "with_save_file",
+                "with_load_file",
+                "with_pick_file",
+                "with_pick_directory"
+            ]
+        );
+    }
+
+    #[test]
+    fn no_attributes() {
+        let input: DeriveInput = parse_quote! {
+            struct LevelFile;
+        };
+
+        let expanded = expand(input).unwrap().to_string();
+        assert!(expanded.contains("DialogKind for LevelFile"));
+        assert!(expanded.contains("fn register"));
+        assert!(!expanded.contains("with_"));
+    }
+
+    #[test]
+    fn generics_kept() {
+        let input: DeriveInput = parse_quote! {
+            #[dialog(save)]
+            struct LevelFile<T: Send + Sync + 'static>(T);
+        };
+
+        let expanded = expand(input).unwrap().to_string();
+        assert!(expanded.contains("for LevelFile < T >"));
+        assert!(expanded.contains(". with_save_file :: < Self > ()"));
+    }
+
+    #[test]
+    fn unknown_attribute() {
+        let input: DeriveInput = parse_quote! {
+            #[dialog(save, pick_files)]
+            struct LevelFile;
+        };
+
+        assert!(expand_error(input).starts_with("expected `title`"));
+    }
+
+    #[test]
+    fn malformed_attributes() {
+        let title_without_value: DeriveInput = parse_quote! {
+            #[dialog(title)]
+            struct LevelFile;
+        };
+        assert!(expand_error(title_without_value).contains("expected `=`"));
+
+        let title_not_string: DeriveInput = parse_quote! {
+            #[dialog(title = 1)]
+            struct LevelFile;
+        };
+        assert!(expand_error(title_not_string).contains("expected string literal"));
+
+        let empty_filter: DeriveInput = parse_quote! {
+            #[dialog(filter())]
+            struct LevelFile;
+        };
+        assert_eq!(
+            expand_error(empty_filter),
+            "expected filter name and extensions"
+        );
+
+        let filter_without_extensions: DeriveInput = parse_quote! {
+            #[dialog(filter("Level"))]
+            struct LevelFile;
+        };
+        assert_eq!(
+            expand_error(filter_without_extensions),
+            "expected at least one extension"
+        );
+
+        let filter_not_string: DeriveInput = parse_quote! {
+            #[dialog(filter("Level", lvl))]
+            struct LevelFile;
+        };
+        assert!(expand_error(filter_not_string).contains("expected string literal"));
+    }
+}
@@ -0,0 +1,49 @@
+//! Example showing how to open dialogs one after another in single async
+//! task, first pick directory and then save file into it.
+//!
+//! Does not work on wasm.
+
+use bevy::{log::LogPlugin, prelude::*, tasks::AsyncComputeTaskPool};
+use bevy_file_dialog::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(MinimalPlugins)
+        .add_plugins(LogPlugin::default())
+        // Dialogs opened with AsyncDialog don't need the markers to be registered
+        .add_plugins(FileDialogPlugin::new())
+        .add_systems(Startup, export)
+        .run();
+}
+
+struct ExportFile;
+
+fn export() {
+    AsyncComputeTaskPool::get()
+        .spawn(async {
+            let DialogOutcome::DirectoryPicked(picked) = AsyncDialog::new()
+                .set_title("Export directory")
+                .pick_directory_path::<ExportFile>()
+                .await
+            else {
+                eprintln!("Export canceled");
+                return;
+            };
+
+            // The next dialog opens only after the first one completed
+            let outcome = AsyncDialog::new()
+                .set_directory(&picked.path)
+                .set_file_name("export.txt")
+                .save_file::<ExportFile>(b"exported".to_vec())
+                .await;
+
+            match outcome {
+                DialogOutcome::Saved(saved) => match saved.result {
+                    Ok(_) => eprintln!("Exported to {:?}", saved.path),
+                    Err(err) => eprintln!("Failed to export {}: {}", saved.file_name, err),
+                },
+                _ => eprintln!("Export canceled"),
+            }
+        })
+        .detach();
+}
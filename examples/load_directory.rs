@@ -0,0 +1,50 @@
+//! Example showing how to load all files with matching extension from a
+//! directory picked with a dialog.
+//!
+//! Does not work on wasm.
+
+use bevy::{log::LogPlugin, prelude::*};
+use bevy_file_dialog::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(MinimalPlugins)
+        .add_plugins(LogPlugin::default())
+        // Add the file dialog plugin and specify that we want to load `ByteLenContents`
+        .add_plugins(FileDialogPlugin::new().with_load_file::<ByteLenContents>())
+        .add_systems(Startup, load)
+        .add_systems(Update, (file_loaded, directory_loaded))
+        .run();
+}
+
+struct ByteLenContents;
+
+fn load(mut commands: Commands) {
+    commands
+        .dialog()
+        .add_filter("Text", &["txt", "md"])
+        .load_directory_recursive::<ByteLenContents>();
+}
+
+fn file_loaded(mut ev_loaded: MessageReader<DialogFileLoaded<ByteLenContents>>) {
+    for ev in ev_loaded.read() {
+        eprintln!(
+            "Loaded file {} with size of {} bytes",
+            ev.file_name,
+            ev.contents.len()
+        );
+    }
+}
+
+fn directory_loaded(mut ev_loaded: MessageReader<DialogDirectoryLoaded<ByteLenContents>>) {
+    for ev in ev_loaded.read() {
+        eprintln!(
+            "Loaded {} files from {:?}, skipped {}",
+            ev.loaded, ev.path, ev.skipped
+        );
+
+        for (path, err) in &ev.errors {
+            eprintln!("Failed to load {:?}: {}", path, err);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Example showing how to load a large file while reporting progress. Press
+//! Escape to cancel the load.
+//!
+//! Does not work on wasm.
+
+use bevy::{log::LogPlugin, prelude::*};
+use bevy_file_dialog::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(MinimalPlugins)
+        .add_plugins(LogPlugin::default())
+        .add_plugins(bevy::input::InputPlugin)
+        // Add the file dialog plugin and specify that we want to load `LargeFile`
+        .add_plugins(FileDialogPlugin::new().with_load_file::<LargeFile>())
+        .add_systems(Startup, load)
+        .add_systems(Update, (progress, file_loaded, load_canceled, cancel))
+        .run();
+}
+
+struct LargeFile;
+
+#[derive(Resource)]
+struct Loading(FileLoadHandle);
+
+fn load(mut commands: Commands) {
+    let handle = commands.dialog().load_file_streaming::<LargeFile>();
+    commands.insert_resource(Loading(handle));
+}
+
+fn cancel(keys: Res<ButtonInput<KeyCode>>, loading: Res<Loading>) {
+    if keys.just_pressed(KeyCode::Escape) {
+        loading.0.cancel();
+    }
+}
+
+fn progress(mut ev_progress: MessageReader<DialogFileLoadProgress<LargeFile>>) {
+    for ev in ev_progress.read() {
+        eprintln!("Loading {}: {:.1}%", ev.file_name, ev.fraction() * 100.0);
+    }
+}
+
+fn file_loaded(mut ev_loaded: MessageReader<DialogFileLoaded<LargeFile>>) {
+    for ev in ev_loaded.read() {
+        eprintln!(
+            "Loaded file {} with size of {} bytes",
+            ev.file_name,
+            ev.contents.len()
+        );
+    }
+}
+
+fn load_canceled(mut ev_canceled: MessageReader<DialogFileLoadCanceled<LargeFile>>) {
+    for _ in ev_canceled.read() {
+        eprintln!("Load canceled");
+    }
+}
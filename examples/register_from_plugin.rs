@@ -0,0 +1,40 @@
+use bevy::{log::LogPlugin, prelude::*};
+use bevy_file_dialog::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(MinimalPlugins)
+        .add_plugins(LogPlugin::default())
+        // The file dialog plugin does not need to know about the markers used
+        // by other plugins
+        .add_plugins(FileDialogPlugin::new())
+        .add_plugins(LevelPlugin)
+        .run();
+}
+
+struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        // Register the marker from the plugin that uses it
+        app.register_file_dialog_load::<LevelFile>()
+            .add_systems(Startup, load)
+            .add_systems(Update, level_loaded);
+    }
+}
+
+struct LevelFile;
+
+fn load(mut commands: Commands) {
+    commands.dialog().load_file::<LevelFile>();
+}
+
+fn level_loaded(mut ev_loaded: MessageReader<DialogFileLoaded<LevelFile>>) {
+    for ev in ev_loaded.read() {
+        eprintln!(
+            "Loaded level {} with size of {} bytes",
+            ev.file_name,
+            ev.contents.len()
+        );
+    }
+}
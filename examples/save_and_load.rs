@@ -49,18 +49,14 @@ fn dialog(mut commands: Commands, keys: Res<ButtonInput<KeyCode>>) {
             commands
                 .dialog()
                 .add_filter("Text", &["txt"])
-                .load_file::<TextFileContents>();
+                .load_text::<TextFileContents>();
         }
     }
 }
 
-fn file_loaded(mut ev_loaded: MessageReader<DialogFileLoaded<TextFileContents>>) {
+fn file_loaded(mut ev_loaded: MessageReader<DialogTextLoaded<TextFileContents>>) {
     for ev in ev_loaded.read() {
-        eprintln!(
-            "Loaded file {} with contents '{}'",
-            ev.file_name,
-            std::str::from_utf8(&ev.contents).unwrap()
-        );
+        eprintln!("Loaded file {} with contents '{}'", ev.file_name, ev.text);
     }
 }
 
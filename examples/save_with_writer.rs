@@ -0,0 +1,49 @@
+//! Example showing how to write large file directly from a producer after the
+//! user picks where to save it, while reporting progress.
+//!
+//! Does not work on wasm.
+
+use std::io::Write;
+
+use bevy::{log::LogPlugin, prelude::*};
+use bevy_file_dialog::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(MinimalPlugins)
+        .add_plugins(LogPlugin::default())
+        // Add the file dialog plugin and specify that we want to save `LargeExport`
+        .add_plugins(FileDialogPlugin::new().with_save_file::<LargeExport>())
+        .add_systems(Startup, save)
+        .add_systems(Update, (progress, file_saved))
+        .run();
+}
+
+struct LargeExport;
+
+fn save(mut commands: Commands) {
+    commands
+        .dialog()
+        .set_file_name("export.csv")
+        .save_file_with_writer::<LargeExport>(|writer| {
+            for i in 0..10_000_000 {
+                writeln!(writer, "{},{}", i, i * i)?;
+            }
+            Ok(())
+        });
+}
+
+fn progress(mut ev_progress: MessageReader<DialogFileSaveProgress<LargeExport>>) {
+    for ev in ev_progress.read() {
+        eprintln!("Saving {}: {} bytes written", ev.file_name, ev.written);
+    }
+}
+
+fn file_saved(mut ev_saved: MessageReader<DialogFileSaved<LargeExport>>) {
+    for ev in ev_saved.read() {
+        match ev.result {
+            Ok(_) => eprintln!("File {} successfully saved", ev.file_name),
+            Err(ref err) => eprintln!("Failed to save {}: {}", ev.file_name, err),
+        }
+    }
+}
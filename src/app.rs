@@ -0,0 +1,279 @@
+use bevy_app::prelude::*;
+
+use crate::callback::register_dialog_callbacks;
+use crate::{
+    register_load_file, register_outcome_message, register_save_file, DialogKind, FileDialogPlugin,
+    LoadContents, SaveContents, VersionedFormat,
+};
+
+/// Extension trait for [`App`] that allows registering dialog markers from
+/// any plugin, independently of [`FileDialogPlugin`].
+///
+/// All functions are idempotent, registering the same marker multiple times
+/// or both here and with [`FileDialogPlugin`] is fine. They can be called
+/// before or after [`FileDialogPlugin`] is added, or without it at all, each
+/// of them also registers what [`FileDialog::load_file_then`] and other
+/// `*_then` functions need to run their callbacks.
+///
+/// [`FileDialog::load_file_then`]: crate::FileDialog::load_file_then
+pub trait FileDialogAppExt {
+    /// Same as [`FileDialogPlugin::with_save_file::<T>`].
+    fn register_file_dialog_save<T: SaveContents>(&mut self) -> &mut Self;
+
+    /// Same as [`FileDialogPlugin::with_load_file::<T>`].
+    fn register_file_dialog_load<T: LoadContents>(&mut self) -> &mut Self;
+
+    /// Same as [`FileDialogPlugin::with_pick_directory::<T>`].
+    ///
+    /// Does not exist in `wasm32`.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn register_file_dialog_pick_directory<T: crate::PickDirectoryPath>(&mut self) -> &mut Self;
+
+    /// Same as [`FileDialogPlugin::with_pick_file::<T>`].
+    ///
+    /// Does not exist in `wasm32`.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn register_file_dialog_pick_file<T: crate::PickFilePath>(&mut self) -> &mut Self;
+
+    /// Same as [`FileDialogPlugin::with_max_file_size::<T>`], the latest
+    /// limit is used when it is set multiple times.
+    fn set_file_dialog_max_file_size<T: LoadContents>(&mut self, max_size: u64) -> &mut Self;
+
+    /// Same as [`FileDialogPlugin::with_file_format::<T>`], the latest format
+    /// is used when it is set multiple times.
+    fn set_file_dialog_format<T: Send + Sync + 'static>(
+        &mut self,
+        format: VersionedFormat,
+    ) -> &mut Self;
+
+    /// Same as [`FileDialogPlugin::with_dialog_kind::<T>`].
+    fn register_file_dialog_kind<T: DialogKind>(&mut self) -> &mut Self;
+
+    /// Same as [`FileDialogPlugin::with_outcome_message::<T>`].
+    fn register_file_dialog_outcome<T: Send + Sync + 'static>(&mut self) -> &mut Self;
+
+    /// Same as [`FileDialogPlugin::with_batch_messages::<T>`].
+    fn register_file_dialog_batch<T: Send + Sync + 'static>(&mut self) -> &mut Self;
+
+    /// Same as [`FileDialogPlugin::with_dynamic_dialogs`].
+    fn register_file_dialog_dynamic(&mut self) -> &mut Self;
+
+    /// Same as [`FileDialogPlugin::with_requests`].
+    fn register_file_dialog_requests(&mut self) -> &mut Self;
+
+    /// Register only what [`FileDialog::load_file_then`] and other `*_then`
+    /// functions need, which [`FileDialogPlugin`] always registers. Useful
+    /// when all dialogs run callbacks and no marker is registered.
+    ///
+    /// [`FileDialog::load_file_then`]: crate::FileDialog::load_file_then
+    fn register_file_dialog_callbacks(&mut self) -> &mut Self;
+
+    /// Same as [`FileDialogPlugin::with_remote_methods`].
+    ///
+    /// Requires `remote` feature, does not exist in `wasm32`.
+    #[cfg(all(feature = "remote", not(target_arch = "wasm32")))]
+    fn register_file_dialog_remote(&mut self) -> &mut Self;
+}
+
+impl FileDialogAppExt for App {
+    fn register_file_dialog_save<T: SaveContents>(&mut self) -> &mut Self {
+        register_dialog_callbacks(self);
+        register_save_file::<T>(self);
+        self
+    }
+
+    fn register_file_dialog_load<T: LoadContents>(&mut self) -> &mut Self {
+        register_dialog_callbacks(self);
+        register_load_file::<T>(self);
+        self
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn register_file_dialog_pick_directory<T: crate::PickDirectoryPath>(&mut self) -> &mut Self {
+        register_dialog_callbacks(self);
+        crate::pick::register_pick_directory::<T>(self);
+        self
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn register_file_dialog_pick_file<T: crate::PickFilePath>(&mut self) -> &mut Self {
+        register_dialog_callbacks(self);
+        crate::pick::register_pick_file::<T>(self);
+        self
+    }
+
+    fn set_file_dialog_max_file_size<T: LoadContents>(&mut self, max_size: u64) -> &mut Self {
+        register_dialog_callbacks(self);
+        for action in FileDialogPlugin::new().with_max_file_size::<T>(max_size).0 {
+            action(self);
+        }
+        self
+    }
+
+    fn set_file_dialog_format<T: Send + Sync + 'static>(
+        &mut self,
+        format: VersionedFormat,
+    ) -> &mut Self {
+        register_dialog_callbacks(self);
+        for action in FileDialogPlugin::new().with_file_format::<T>(format).0 {
+            action(self);
+        }
+        self
+    }
+
+    fn register_file_dialog_kind<T: DialogKind>(&mut self) -> &mut Self {
+        register_dialog_callbacks(self);
+        for action in FileDialogPlugin::new().with_dialog_kind::<T>().0 {
+            action(self);
+        }
+        self
+    }
+
+    fn register_file_dialog_outcome<T: Send + Sync + 'static>(&mut self) -> &mut Self {
+        register_dialog_callbacks(self);
+        register_outcome_message::<T>(self);
+        self
+    }
+
+    fn register_file_dialog_batch<T: Send + Sync + 'static>(&mut self) -> &mut Self {
+        register_dialog_callbacks(self);
+        crate::batch::register_batch_messages::<T>(self);
+        self
+    }
+
+    fn register_file_dialog_dynamic(&mut self) -> &mut Self {
+        register_dialog_callbacks(self);
+        crate::dynamic::register_dynamic_dialogs(self);
+        self
+    }
+
+    fn register_file_dialog_requests(&mut self) -> &mut Self {
+        register_dialog_callbacks(self);
+        crate::request::register_requests(self);
+        self
+    }
+
+    fn register_file_dialog_callbacks(&mut self) -> &mut Self {
+        register_dialog_callbacks(self);
+        self
+    }
+
+    #[cfg(all(feature = "remote", not(target_arch = "wasm32")))]
+    fn register_file_dialog_remote(&mut self) -> &mut Self {
+        register_dialog_callbacks(self);
+        crate::remote::register_remote(self);
+        self
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use bevy_ecs::prelude::*;
+
+    use super::*;
+    use crate::callback::PendingCallback;
+    use crate::format::FileFormat;
+    use crate::testing::{self, respond};
+    use crate::{DialogOutcome, FileDialogExt, MaxFileSize, MockDialogBackend, StreamSender};
+
+    struct SaveFile;
+
+    fn first_systems(app: &mut App) -> usize {
+        app.get_schedule(First).unwrap().systems_len()
+    }
+
+    fn callback_sender(app: &App) -> crossbeam_channel::Sender<PendingCallback> {
+        app.world()
+            .resource::<StreamSender<PendingCallback>>()
+            .0
+            .clone()
+    }
+
+    #[test]
+    fn registration_idempotent() {
+        let mut once = App::new();
+        once.register_file_dialog_save::<SaveFile>();
+
+        let mut twice = App::new();
+        twice
+            .register_file_dialog_save::<SaveFile>()
+            .register_file_dialog_save::<SaveFile>()
+            .register_file_dialog_callbacks();
+
+        assert_eq!(first_systems(&mut once), first_systems(&mut twice));
+    }
+
+    #[test]
+    fn registered_before_plugin() {
+        let mut registered = App::new();
+        registered.add_plugins(FileDialogPlugin::new().with_save_file::<SaveFile>());
+
+        let mut app = App::new();
+        app.register_file_dialog_save::<SaveFile>()
+            .register_file_dialog_save::<SaveFile>();
+        let sender = callback_sender(&app);
+        app.add_plugins(FileDialogPlugin::new().with_save_file::<SaveFile>());
+
+        assert!(sender.same_channel(&callback_sender(&app)));
+        assert_eq!(first_systems(&mut app), first_systems(&mut registered));
+    }
+
+    #[test]
+    fn registered_after_plugin() {
+        let mut registered = App::new();
+        registered.add_plugins(FileDialogPlugin::new().with_save_file::<SaveFile>());
+
+        let mut app = App::new();
+        app.add_plugins(FileDialogPlugin::new().with_save_file::<SaveFile>());
+        let sender = callback_sender(&app);
+        app.register_file_dialog_save::<SaveFile>()
+            .register_file_dialog_callbacks();
+
+        assert!(sender.same_channel(&callback_sender(&app)));
+        assert_eq!(first_systems(&mut app), first_systems(&mut registered));
+    }
+
+    #[test]
+    fn settings_without_plugin() {
+        let mut app = App::new();
+        app.set_file_dialog_max_file_size::<SaveFile>(10)
+            .set_file_dialog_max_file_size::<SaveFile>(20)
+            .set_file_dialog_format::<SaveFile>(VersionedFormat::new(*b"SAV", 1));
+
+        assert_eq!(app.world().resource::<MaxFileSize<SaveFile>>().0, 20);
+        assert!(app.world().contains_resource::<FileFormat<SaveFile>>());
+        assert!(app
+            .world()
+            .contains_resource::<StreamSender<PendingCallback>>());
+    }
+
+    #[derive(Resource, Default)]
+    struct SavedPath(Option<std::path::PathBuf>);
+
+    #[test]
+    fn callbacks_without_plugin() {
+        testing::init_task_pools();
+
+        let mut app = App::new();
+        app.init_resource::<MockDialogBackend>()
+            .init_resource::<SavedPath>()
+            .register_file_dialog_save::<SaveFile>();
+
+        let path = testing::test_directory("app_callbacks").join("saved.txt");
+        respond(&mut app, [&path]);
+        app.world_mut()
+            .commands()
+            .dialog()
+            .save_file_then::<SaveFile, _>(
+                b"saved".to_vec(),
+                |In(outcome): In<DialogOutcome<SaveFile>>, mut saved_path: ResMut<SavedPath>| {
+                    if let DialogOutcome::Saved(saved) = outcome {
+                        saved_path.0 = Some(saved.path);
+                    }
+                },
+            );
+
+        testing::update_until(&mut app, |world| world.resource::<SavedPath>().0.is_some());
+        assert_eq!(app.world().resource::<SavedPath>().0, Some(path));
+    }
+}
@@ -0,0 +1,326 @@
+use std::any::TypeId;
+
+use bevy_asset::{Asset, AssetServer};
+use bevy_ecs::prelude::*;
+use bevy_tasks::futures::now_or_never;
+use bevy_utils::prelude::ShortName;
+use rfd::AsyncFileDialog;
+use tracing::warn;
+
+use crate::FileDialog;
+
+/// Filter with extensions of common Bevy asset types, see
+/// [`FileDialog::add_filter_preset`]. Requires `asset` feature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FilterPreset {
+    /// Image formats supported by Bevy's image loader.
+    Images,
+    /// Audio formats supported by Bevy's audio loader.
+    Audio,
+    /// Bevy scenes.
+    Scenes,
+    /// glTF models and scenes.
+    Gltf,
+}
+
+impl FilterPreset {
+    /// All presets.
+    pub const ALL: [FilterPreset; 4] = [
+        FilterPreset::Images,
+        FilterPreset::Audio,
+        FilterPreset::Scenes,
+        FilterPreset::Gltf,
+    ];
+
+    /// Name of the filter displayed in the dialog.
+    pub fn name(self) -> &'static str {
+        match self {
+            FilterPreset::Images => "Images",
+            FilterPreset::Audio => "Audio",
+            FilterPreset::Scenes => "Scenes",
+            FilterPreset::Gltf => "glTF",
+        }
+    }
+
+    /// Extensions of the filter.
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            FilterPreset::Images => &[
+                "png", "jpg", "jpeg", "bmp", "gif", "ico", "tga", "tif", "tiff", "webp", "hdr",
+                "exr", "dds", "ktx2", "basis", "pam", "pbm", "pgm", "ppm", "qoi",
+            ],
+            FilterPreset::Audio => &["ogg", "oga", "spx", "mp3", "flac", "wav"],
+            FilterPreset::Scenes => &["scn", "ron"],
+            FilterPreset::Gltf => &["gltf", "glb"],
+        }
+    }
+}
+
+/// Filter added with [`FileDialog::add_asset_filter`], its extensions are
+/// looked up when the dialog gets opened, because that is the first time the
+/// [`AssetServer`] is available.
+struct AssetFilter {
+    name: String,
+    type_id: TypeId,
+}
+
+/// All filters added with [`FileDialog::add_asset_filter`].
+#[derive(Default)]
+pub(crate) struct AssetFilters(Vec<AssetFilter>);
+
+impl AssetFilters {
+    /// Look up name and extensions of each filter from asset loaders
+    /// registered in [`AssetServer`]. Filters without registered loader, or
+    /// with loader that was only preregistered and is not ready yet, are left
+    /// out with a warning.
+    ///
+    /// [`AssetServer`] only gives out the last loader registered for asset
+    /// type, other loaders of the type are found by looking up loaders of
+    /// extensions of all [`FilterPreset`]s.
+    pub(crate) fn resolve(&self, world: &World) -> Vec<(String, Vec<String>)> {
+        if self.0.is_empty() {
+            return Vec::new();
+        }
+
+        let asset_server = world
+            .get_resource::<AssetServer>()
+            .expect("FileDialog::add_asset_filter requires AssetPlugin");
+
+        self.0
+            .iter()
+            .filter_map(|filter| {
+                // Loaders are registered during app setup, so the lookup is
+                // ready immediately unless the loader was only preregistered.
+                let lookup = asset_server.get_asset_loader_with_asset_type_id(filter.type_id);
+                let loader = match now_or_never(lookup) {
+                    Some(Ok(loader)) => loader,
+                    Some(Err(_)) => {
+                        warn!(
+                            "no asset loader registered for {}, filter is ignored",
+                            filter.name
+                        );
+                        return None;
+                    }
+                    None => {
+                        warn!(
+                            "asset loader for {} is not ready yet, filter is ignored",
+                            filter.name
+                        );
+                        return None;
+                    }
+                };
+
+                let mut extensions: Vec<String> = loader
+                    .extensions()
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect();
+
+                let other_loaders = FilterPreset::ALL
+                    .iter()
+                    .flat_map(|preset| preset.extensions())
+                    .filter(|extension| !extensions.iter().any(|known| known == *extension))
+                    .filter_map(|extension| {
+                        let lookup = asset_server.get_asset_loader_with_extension(extension);
+                        now_or_never(lookup)?.ok()
+                    })
+                    .filter(|other| other.asset_type_id() == filter.type_id)
+                    .collect::<Vec<_>>();
+
+                for other in other_loaders {
+                    for extension in other.extensions() {
+                        if !extensions.iter().any(|known| known == extension) {
+                            extensions.push(extension.to_string());
+                        }
+                    }
+                }
+
+                Some((filter.name.clone(), extensions))
+            })
+            .collect()
+    }
+
+    /// Add resolved filters to `dialog`.
+    pub(crate) fn apply(&self, dialog: AsyncFileDialog, world: &World) -> AsyncFileDialog {
+        self.resolve(world)
+            .into_iter()
+            .fold(dialog, |dialog, (name, extensions)| {
+                dialog.add_filter(name, &extensions)
+            })
+    }
+}
+
+impl FileDialog<'_, '_, '_> {
+    /// Add file extension filter with extensions of the asset loaders
+    /// registered for asset type `A` in [`AssetServer`]. The filter is named
+    /// after the asset type.
+    ///
+    /// The extensions are looked up when the dialog gets opened and the filter
+    /// is added after filters added with [`FileDialog::add_filter`]. Besides
+    /// the last registered loader of `A`, only loaders registered for
+    /// extensions of some [`FilterPreset`] are found. Asset types without
+    /// registered loader are ignored with a warning.
+    ///
+    /// Requires `asset` feature.
+    ///
+    /// # Panics
+    ///
+    /// When the dialog gets opened and [`AssetServer`] does not exist.
+    pub fn add_asset_filter<A: Asset>(mut self) -> Self {
+        self.config.asset_filters.0.push(AssetFilter {
+            name: ShortName::of::<A>().to_string(),
+            type_id: TypeId::of::<A>(),
+        });
+        self.config.has_filters = true;
+        self
+    }
+
+    /// Add file extension filter for common type of Bevy assets.
+    ///
+    /// Requires `asset` feature.
+    pub fn add_filter_preset(self, preset: FilterPreset) -> Self {
+        self.add_filter(preset.name(), preset.extensions())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::prelude::*;
+    use bevy_asset::io::Reader;
+    use bevy_asset::{AssetApp, AssetLoader, AssetPlugin, LoadContext};
+    use bevy_ecs::world::CommandQueue;
+    use bevy_reflect::TypePath;
+    use bevy_tasks::{IoTaskPool, TaskPool};
+
+    use super::*;
+    use crate::FileDialogExt;
+
+    #[derive(Asset, TypePath)]
+    struct Level;
+
+    #[derive(Asset, TypePath)]
+    struct Sound;
+
+    #[derive(TypePath)]
+    struct TestLoader<A> {
+        extensions: &'static [&'static str],
+        asset: fn() -> A,
+    }
+
+    impl<A: Asset> AssetLoader for TestLoader<A> {
+        type Asset = A;
+        type Settings = ();
+        type Error = std::io::Error;
+
+        async fn load(
+            &self,
+            _reader: &mut dyn Reader,
+            _settings: &(),
+            _load_context: &mut LoadContext<'_>,
+        ) -> Result<A, std::io::Error> {
+            Ok((self.asset)())
+        }
+
+        fn extensions(&self) -> &[&str] {
+            self.extensions
+        }
+    }
+
+    fn app() -> App {
+        IoTaskPool::get_or_init(TaskPool::new);
+
+        let mut app = App::new();
+        app.add_plugins(AssetPlugin::default())
+            .init_asset::<Level>()
+            .init_asset::<Sound>();
+        app
+    }
+
+    fn resolve<A: Asset>(app: &App) -> Vec<(String, Vec<String>)> {
+        let mut filters = AssetFilters::default();
+        filters.0.push(AssetFilter {
+            name: ShortName::of::<A>().to_string(),
+            type_id: TypeId::of::<A>(),
+        });
+        filters.resolve(app.world())
+    }
+
+    #[test]
+    fn resolve_single_loader() {
+        let mut app = app();
+        app.register_asset_loader(TestLoader {
+            extensions: &["level"],
+            asset: || Level,
+        });
+
+        assert_eq!(
+            resolve::<Level>(&app),
+            [("Level".to_string(), vec!["level".to_string()])]
+        );
+    }
+
+    #[test]
+    fn resolve_multiple_loaders() {
+        let mut app = app();
+        app.register_asset_loader(TestLoader {
+            extensions: &["ron", "level"],
+            asset: || Level,
+        })
+        .register_asset_loader(TestLoader {
+            extensions: &["ogg"],
+            asset: || Sound,
+        })
+        .register_asset_loader(TestLoader {
+            extensions: &["gltf", "glb"],
+            asset: || Level,
+        });
+
+        assert_eq!(
+            resolve::<Level>(&app),
+            [(
+                "Level".to_string(),
+                vec![
+                    "gltf".to_string(),
+                    "glb".to_string(),
+                    "ron".to_string(),
+                    "level".to_string()
+                ]
+            )]
+        );
+        assert_eq!(
+            resolve::<Sound>(&app),
+            [("Sound".to_string(), vec!["ogg".to_string()])]
+        );
+    }
+
+    #[test]
+    fn resolve_unregistered_loader() {
+        let mut app = app();
+        app.register_asset_loader(TestLoader {
+            extensions: &["level"],
+            asset: || Level,
+        });
+
+        assert!(resolve::<Sound>(&app).is_empty());
+    }
+
+    #[test]
+    fn filter_presets() {
+        let world = World::new();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+
+        for preset in FilterPreset::ALL {
+            let dialog = commands.dialog().add_filter_preset(preset);
+            assert!(dialog.config.has_filters);
+            assert_eq!(dialog.extensions, preset.extensions());
+            assert!(!preset.name().is_empty());
+        }
+
+        assert_eq!(FilterPreset::Gltf.extensions(), ["gltf", "glb"]);
+        assert!(FilterPreset::Images.extensions().contains(&"png"));
+        assert!(FilterPreset::Audio.extensions().contains(&"ogg"));
+        assert!(FilterPreset::Scenes.extensions().contains(&"scn"));
+    }
+}
@@ -0,0 +1,219 @@
+use std::marker::PhantomData;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use crossbeam_channel::bounded;
+
+use crate::{
+    handle_message, DialogFileLoaded, FileDialogPlugin, LoadContents, StreamReceiver, StreamSender,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::DialogFilePicked;
+
+/// Event that gets sent when files picked with
+/// [`FileDialog::load_multiple_files`](crate::FileDialog::load_multiple_files)
+/// get loaded, in addition to separate [`DialogFileLoaded<T>`] for each file.
+///
+/// Requires [`FileDialogPlugin::with_batch_messages::<T>`].
+#[derive(Message)]
+pub struct DialogFilesLoaded<T: LoadContents> {
+    /// Loaded files in the order they were selected in. Files that were too
+    /// large or could not be decoded are not included, their events are
+    /// sent separately.
+    pub files: Vec<DialogFileLoaded<T>>,
+
+    marker: PhantomData<T>,
+}
+
+impl<T: LoadContents> DialogFilesLoaded<T> {
+    pub(crate) fn new(files: Vec<DialogFileLoaded<T>>) -> Self {
+        Self {
+            files,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Event that gets sent when file paths get picked with
+/// [`FileDialog::pick_multiple_file_paths`](crate::FileDialog::pick_multiple_file_paths),
+/// in addition to separate [`DialogFilePicked<T>`] for each path.
+///
+/// Requires [`FileDialogPlugin::with_batch_messages::<T>`]. Does not exist in
+/// `wasm32`.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Message)]
+pub struct DialogPathsPicked<T: crate::PickFilePath> {
+    /// Picked files in the order they were selected in.
+    pub files: Vec<DialogFilePicked<T>>,
+
+    marker: PhantomData<T>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: crate::PickFilePath> DialogPathsPicked<T> {
+    pub(crate) fn new(files: Vec<DialogFilePicked<T>>) -> Self {
+        Self {
+            files,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Register batch events for marker `T`, does nothing if they are already
+/// registered.
+pub(crate) fn register_batch_messages<T: Send + Sync + 'static>(app: &mut App) {
+    if app
+        .world()
+        .contains_resource::<StreamSender<DialogFilesLoaded<T>>>()
+    {
+        return;
+    }
+
+    let (tx, rx) = bounded::<DialogFilesLoaded<T>>(1);
+    app.insert_resource(StreamSender(tx));
+    app.insert_resource(StreamReceiver(rx));
+    app.add_message::<DialogFilesLoaded<T>>();
+    app.add_systems(First, handle_message::<DialogFilesLoaded<T>>);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let (tx, rx) = bounded::<DialogPathsPicked<T>>(1);
+        app.insert_resource(StreamSender(tx));
+        app.insert_resource(StreamReceiver(rx));
+        app.add_message::<DialogPathsPicked<T>>();
+        app.add_systems(First, handle_message::<DialogPathsPicked<T>>);
+    }
+}
+
+impl FileDialogPlugin {
+    /// Send single [`DialogFilesLoaded<T>`] with all files selected with
+    /// [`FileDialog::load_multiple_files`](crate::FileDialog::load_multiple_files)
+    /// in addition to separate [`DialogFileLoaded<T>`] for each file, so that
+    /// files chosen together can be handled together. The same applies to
+    /// `DialogPathsPicked<T>` and
+    /// `FileDialog::pick_multiple_file_paths` on native platforms.
+    ///
+    /// Systems reading the separate events keep working, the batch shares
+    /// the contents of loaded files with them instead of copying them.
+    ///
+    /// Requires [`FileDialogPlugin::with_load_file::<T>`] or
+    /// `FileDialogPlugin::with_pick_file::<T>`.
+    pub fn with_batch_messages<T: Send + Sync + 'static>(mut self) -> Self {
+        self.0.push(Box::new(register_batch_messages::<T>));
+        self
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::fs;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use bevy_tasks::{AsyncComputeTaskPool, TaskPool};
+
+    use super::*;
+    use crate::{FileDialogExt, MockDialogBackend};
+
+    struct Batch;
+
+    /// Number of separate and batch events received, with contents of
+    /// their files.
+    #[derive(Resource, Default)]
+    struct Received {
+        single: usize,
+        batches: Vec<usize>,
+        single_contents: Vec<Arc<Vec<u8>>>,
+        batch_contents: Vec<Arc<Vec<u8>>>,
+    }
+
+    fn count(
+        mut ev_loaded: MessageReader<DialogFileLoaded<Batch>>,
+        mut ev_batch: MessageReader<DialogFilesLoaded<Batch>>,
+        mut received: ResMut<Received>,
+    ) {
+        for ev in ev_loaded.read() {
+            received.single += 1;
+            received.single_contents.push(ev.contents.clone());
+        }
+
+        for batch in ev_batch.read() {
+            received.batches.push(batch.files.len());
+            let contents = batch.files.iter().map(|file| file.contents.clone());
+            received.batch_contents.extend(contents);
+        }
+    }
+
+    /// Check that the batch holds the same contents as the separate events,
+    /// instead of their copies.
+    fn assert_contents_shared(received: &Received) {
+        assert_eq!(received.batch_contents.len(), 2);
+        for contents in &received.batch_contents {
+            assert!(received
+                .single_contents
+                .iter()
+                .any(|single| Arc::ptr_eq(single, contents)));
+        }
+    }
+
+    fn load_two_files(send_as_loaded: bool) -> Received {
+        AsyncComputeTaskPool::get_or_init(TaskPool::new);
+
+        let directory =
+            std::env::temp_dir().join(format!("bevy_file_dialog_batch_{send_as_loaded}"));
+        fs::create_dir_all(&directory).unwrap();
+        let paths = [directory.join("a.txt"), directory.join("b.txt")];
+        for path in &paths {
+            fs::write(path, b"contents").unwrap();
+        }
+
+        let mut app = App::new();
+        app.add_plugins(
+            FileDialogPlugin::new()
+                .with_load_file::<Batch>()
+                .with_batch_messages::<Batch>(),
+        );
+        app.init_resource::<MockDialogBackend>();
+        app.init_resource::<Received>();
+        app.add_systems(Update, count);
+        app.world_mut()
+            .resource_mut::<MockDialogBackend>()
+            .push_pick_multiple(paths);
+
+        app.world_mut()
+            .commands()
+            .dialog()
+            .set_send_as_loaded(send_as_loaded)
+            .load_multiple_files::<Batch>();
+        app.world_mut().flush();
+
+        for _ in 0..100 {
+            app.update();
+            if !app.world().resource::<Received>().batches.is_empty() {
+                break;
+            }
+
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        app.update();
+        app.world_mut().remove_resource::<Received>().unwrap()
+    }
+
+    #[test]
+    fn batch_sent_with_single_events() {
+        let received = load_two_files(false);
+        assert_eq!(received.single, 2);
+        assert_eq!(received.batches, [2]);
+        assert_contents_shared(&received);
+    }
+
+    #[test]
+    fn batch_sent_with_events_sent_as_loaded() {
+        let received = load_two_files(true);
+        assert_eq!(received.single, 2);
+        assert_eq!(received.batches, [2]);
+        assert_contents_shared(&received);
+    }
+}
@@ -0,0 +1,307 @@
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::SystemId;
+use crossbeam_channel::bounded;
+
+use crate::{DialogOutcome, FileDialog, LoadContents, SaveContents, StreamReceiver, StreamSender};
+
+/// Callback waiting to be run with the outcome of its dialog.
+pub(crate) type PendingCallback = Box<dyn FnOnce(&mut World) + Send>;
+
+/// One-shot system that runs with [`DialogOutcome<T>`] as its input when
+/// dialog opened with [`FileDialog::load_file_then`] or other `*_then`
+/// function completes.
+///
+/// Implemented for systems taking `In<DialogOutcome<T>>`, which get
+/// registered when the dialog completes and unregistered after they run, and
+/// for [`SystemId`] of systems you registered yourself with
+/// [`Commands::register_system`], which stay registered.
+pub trait DialogCallback<T: Send + Sync + 'static, M>: Send + 'static {
+    /// Queue the callback to run with `outcome`.
+    fn queue(self, commands: &mut Commands, outcome: DialogOutcome<T>);
+}
+
+impl<T, M, S> DialogCallback<T, (M,)> for S
+where
+    T: Send + Sync + 'static,
+    S: IntoSystem<In<DialogOutcome<T>>, (), M> + Send + 'static,
+{
+    fn queue(self, commands: &mut Commands, outcome: DialogOutcome<T>) {
+        let system = commands.register_system(self);
+        commands.run_system_with(system, outcome);
+        commands.unregister_system(system);
+    }
+}
+
+impl<T: Send + Sync + 'static> DialogCallback<T, ()> for SystemId<In<DialogOutcome<T>>> {
+    fn queue(self, commands: &mut Commands, outcome: DialogOutcome<T>) {
+        commands.run_system_with(self, outcome);
+    }
+}
+
+/// Register the system that runs callbacks of completed dialogs, does nothing
+/// if it is already registered.
+pub(crate) fn register_dialog_callbacks(app: &mut App) {
+    if app
+        .world()
+        .contains_resource::<StreamSender<PendingCallback>>()
+    {
+        return;
+    }
+
+    let (tx, rx) = bounded::<PendingCallback>(1);
+    app.insert_resource(StreamSender(tx));
+    app.insert_resource(StreamReceiver(rx));
+    app.add_systems(First, run_dialog_callbacks);
+}
+
+fn run_dialog_callbacks(world: &mut World) {
+    let callbacks: Vec<_> = world
+        .resource::<StreamReceiver<PendingCallback>>()
+        .try_iter()
+        .collect();
+
+    for callback in callbacks {
+        callback(world);
+    }
+
+    world.flush();
+}
+
+/// Returns function that sends the outcome together with `callback` to
+/// [`run_dialog_callbacks`].
+fn deliver_to<T, M>(
+    callback: impl DialogCallback<T, M>,
+) -> impl FnOnce(&mut World) -> Box<dyn FnOnce(DialogOutcome<T>) + Send> + Send + 'static
+where
+    T: Send + Sync + 'static,
+{
+    move |world| {
+        let sender = world
+            .get_resource::<StreamSender<PendingCallback>>()
+            .expect("FileDialogPlugin not added to the app")
+            .0
+            .clone();
+
+        Box::new(move |outcome| {
+            let callback: PendingCallback =
+                Box::new(move |world| callback.queue(&mut world.commands(), outcome));
+            sender.send(callback).unwrap();
+        })
+    }
+}
+
+impl FileDialog<'_, '_, '_> {
+    /// Open save file dialog and save the `contents` to that file, same as
+    /// [`FileDialog::save_file`], but instead of sending events run
+    /// `callback` with [`DialogOutcome::Saved`] or
+    /// [`DialogOutcome::Canceled`] when the dialog completes.
+    ///
+    /// The callback runs in the [`First`] schedule, like the events of other
+    /// dialogs get sent. It can be any system that takes
+    /// `In<DialogOutcome<T>>`, or [`SystemId`] of such system, see
+    /// [`DialogCallback`]. Does not require
+    /// [`FileDialogPlugin::with_save_file::<T>`](crate::FileDialogPlugin::with_save_file).
+    pub fn save_file_then<T: SaveContents, M>(
+        self,
+        contents: Vec<u8>,
+        callback: impl DialogCallback<T, M>,
+    ) {
+        self.open_save_file(contents, deliver_to(callback));
+    }
+
+    /// Open pick file dialog and load its contents, same as
+    /// [`FileDialog::load_file`], but instead of sending events run
+    /// `callback` with [`DialogOutcome::Loaded`], [`DialogOutcome::TooLarge`],
+    /// [`DialogOutcome::Failed`] or [`DialogOutcome::Canceled`] when the
+    /// dialog completes.
+    ///
+    /// See [`FileDialog::save_file_then`] for when and how the callback runs.
+    pub fn load_file_then<T: LoadContents, M>(self, callback: impl DialogCallback<T, M>) {
+        self.open_load_file(deliver_to(callback));
+    }
+
+    /// Open pick file dialog, same as [`FileDialog::pick_file_path`], but
+    /// instead of sending events run `callback` with
+    /// [`DialogOutcome::Picked`] or [`DialogOutcome::Canceled`] when the
+    /// dialog completes.
+    ///
+    /// See [`FileDialog::save_file_then`] for when and how the callback runs.
+    /// Does not exist in `wasm32`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn pick_file_path_then<T: crate::PickFilePath, M>(
+        self,
+        callback: impl DialogCallback<T, M>,
+    ) {
+        self.open_pick_file(deliver_to(callback));
+    }
+
+    /// Open pick directory dialog, same as
+    /// [`FileDialog::pick_directory_path`], but instead of sending events run
+    /// `callback` with [`DialogOutcome::DirectoryPicked`] or
+    /// [`DialogOutcome::Canceled`] when the dialog completes.
+    ///
+    /// See [`FileDialog::save_file_then`] for when and how the callback runs.
+    /// Does not exist in `wasm32`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn pick_directory_path_then<T: crate::PickDirectoryPath, M>(
+        self,
+        callback: impl DialogCallback<T, M>,
+    ) {
+        self.open_pick_directory(deliver_to(callback));
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::fs;
+
+    use bevy_ecs::system::SystemIdMarker;
+
+    use super::*;
+    use crate::testing::{self, respond, test_directory, update_until};
+    use crate::{FileDialogExt, FileDialogPlugin};
+
+    struct Notes;
+
+    /// Outcomes received by callbacks, described by their variant and file.
+    #[derive(Resource, Default)]
+    struct Outcomes(Vec<String>);
+
+    fn record(In(outcome): In<DialogOutcome<Notes>>, mut outcomes: ResMut<Outcomes>) {
+        outcomes.0.push(match outcome {
+            DialogOutcome::Loaded(loaded) => format!(
+                "loaded {} {}",
+                loaded.file_name,
+                String::from_utf8_lossy(&loaded.contents)
+            ),
+            DialogOutcome::Saved(saved) => format!("saved {}", saved.file_name),
+            DialogOutcome::Picked(picked) => format!("picked {}", picked.path.display()),
+            DialogOutcome::DirectoryPicked(picked) => {
+                format!("directory {}", picked.path.display())
+            }
+            DialogOutcome::TooLarge(too_large) => format!("too large {}", too_large.file_name),
+            DialogOutcome::Failed(failed) => format!("failed {}", failed.file_name),
+            DialogOutcome::Canceled => "canceled".to_string(),
+        });
+    }
+
+    fn app() -> App {
+        let mut app = testing::app(FileDialogPlugin::new());
+        app.init_resource::<Outcomes>();
+        app
+    }
+
+    fn outcomes(app: &App) -> &[String] {
+        &app.world().resource::<Outcomes>().0
+    }
+
+    /// Update until `count` callbacks ran, and some more to catch callbacks
+    /// that would run more than once.
+    fn update_until_outcomes(app: &mut App, count: usize) {
+        update_until(app, |world| world.resource::<Outcomes>().0.len() >= count);
+        for _ in 0..3 {
+            app.update();
+        }
+    }
+
+    fn registered_systems(app: &mut App) -> usize {
+        app.world_mut()
+            .query_filtered::<(), With<SystemIdMarker>>()
+            .iter(app.world())
+            .count()
+    }
+
+    #[test]
+    fn system_runs_once_with_outcome() {
+        let directory = test_directory("callback_outcomes");
+        let notes = directory.join("notes.txt");
+        fs::write(&notes, b"notes").unwrap();
+
+        let mut app = app();
+        respond(&mut app, [&notes]);
+        respond(&mut app, [&notes]);
+        respond(&mut app, Vec::<&str>::new());
+        respond(&mut app, [directory.join("saved.txt")]);
+        respond(&mut app, [&directory]);
+
+        let mut commands = app.world_mut().commands();
+        commands.dialog().load_file_then(record);
+        commands.dialog().pick_file_path_then(record);
+        commands.dialog().load_file_then(record);
+        commands.dialog().save_file_then(b"saved".to_vec(), record);
+        commands.dialog().pick_directory_path_then(record);
+
+        update_until_outcomes(&mut app, 5);
+
+        let mut outcomes = outcomes(&app).to_vec();
+        outcomes.sort();
+        let mut expected = vec![
+            "loaded notes.txt notes".to_string(),
+            format!("picked {}", notes.display()),
+            "canceled".to_string(),
+            "saved saved.txt".to_string(),
+            format!("directory {}", directory.display()),
+        ];
+        expected.sort();
+        assert_eq!(outcomes, expected);
+    }
+
+    #[test]
+    fn too_large_and_failed() {
+        let directory = test_directory("callback_errors");
+        let image = directory.join("image.png");
+        fs::write(&image, b"not an image").unwrap();
+
+        let mut app = app();
+        respond(&mut app, [&image]);
+        respond(&mut app, [&image]);
+
+        let mut commands = app.world_mut().commands();
+        commands
+            .dialog()
+            .set_max_file_size(4)
+            .load_file_then(record);
+        commands
+            .dialog()
+            .set_verify_content(true)
+            .load_file_then(record);
+
+        update_until_outcomes(&mut app, 2);
+
+        let mut outcomes = outcomes(&app).to_vec();
+        outcomes.sort();
+        assert_eq!(outcomes, ["failed image.png", "too large image.png"]);
+    }
+
+    #[test]
+    fn one_shot_system_unregistered() {
+        let mut app = app();
+        respond(&mut app, Vec::<&str>::new());
+
+        app.world_mut()
+            .commands()
+            .dialog()
+            .load_file_then::<Notes, _>(record);
+        update_until_outcomes(&mut app, 1);
+
+        assert_eq!(outcomes(&app), ["canceled"]);
+        assert_eq!(registered_systems(&mut app), 0);
+    }
+
+    #[test]
+    fn registered_system_stays_registered() {
+        let mut app = app();
+        respond(&mut app, Vec::<&str>::new());
+        respond(&mut app, Vec::<&str>::new());
+
+        let system = app.world_mut().register_system(record);
+        let mut commands = app.world_mut().commands();
+        commands.dialog().load_file_then(system);
+        commands.dialog().load_file_then(system);
+        update_until_outcomes(&mut app, 2);
+
+        assert_eq!(outcomes(&app), ["canceled", "canceled"]);
+        assert_eq!(registered_systems(&mut app), 1);
+    }
+}
@@ -0,0 +1,162 @@
+use std::io;
+
+use rfd::FileHandle;
+use sha2::{Digest, Sha256};
+
+use crate::LoadError;
+
+const DIGEST_LEN: usize = 32;
+
+/// Where SHA-256 checksum of saved file is stored, see
+/// [`FileDialog::set_checksum`](crate::FileDialog::set_checksum).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Checksum {
+    /// Checksum is not stored nor verified.
+    #[default]
+    None,
+
+    /// Checksum is appended as the last 32 bytes of the file.
+    Embedded,
+
+    /// Checksum is stored next to the file in `<file name>.sha256` in the same
+    /// format as `sha256sum` tool uses.
+    ///
+    /// Does not exist in `wasm32`.
+    #[cfg(not(target_arch = "wasm32"))]
+    Sidecar,
+}
+
+impl Checksum {
+    /// Append checksum to `contents` if it should be embedded.
+    pub(crate) fn embed(self, mut contents: Vec<u8>) -> Vec<u8> {
+        if self == Checksum::Embedded {
+            let digest = Sha256::digest(&contents);
+            contents.extend_from_slice(&digest);
+        }
+
+        contents
+    }
+
+    /// Write sidecar file with checksum of `contents` saved to `file`.
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+    pub(crate) fn write_sidecar(self, file: &FileHandle, contents: &[u8]) -> io::Result<()> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self == Checksum::Sidecar {
+            let line = format!(
+                "{}  {}\n",
+                to_hex(&Sha256::digest(contents)),
+                file.file_name()
+            );
+            std::fs::write(sidecar_path(file), line)?;
+        }
+
+        Ok(())
+    }
+
+    /// Verify checksum of `contents` loaded from `file` and strip the
+    /// embedded checksum.
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+    pub(crate) fn verify(
+        self,
+        file: &FileHandle,
+        mut contents: Vec<u8>,
+    ) -> Result<Vec<u8>, LoadError> {
+        match self {
+            Checksum::None => {}
+            Checksum::Embedded => {
+                if contents.len() < DIGEST_LEN {
+                    return Err(LoadError::ChecksumMismatch);
+                }
+
+                let expected = contents.split_off(contents.len() - DIGEST_LEN);
+                if Sha256::digest(&contents).as_slice() != expected {
+                    return Err(LoadError::ChecksumMismatch);
+                }
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Checksum::Sidecar => {
+                let sidecar = std::fs::read_to_string(sidecar_path(file))
+                    .map_err(LoadError::MissingChecksum)?;
+                let expected = sidecar.split_whitespace().next().unwrap_or_default();
+
+                if !expected.eq_ignore_ascii_case(&to_hex(&Sha256::digest(&contents))) {
+                    return Err(LoadError::ChecksumMismatch);
+                }
+            }
+        }
+
+        Ok(contents)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn sidecar_path(file: &FileHandle) -> std::path::PathBuf {
+    let mut path = file.path().as_os_str().to_owned();
+    path.push(".sha256");
+    path.into()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded() {
+        let file = FileHandle::from(std::path::PathBuf::from("level.bin"));
+
+        let contents = Checksum::Embedded.embed(b"level".to_vec());
+        assert_eq!(contents.len(), 5 + DIGEST_LEN);
+        assert_eq!(
+            Checksum::Embedded.verify(&file, contents).unwrap(),
+            b"level"
+        );
+    }
+
+    #[test]
+    fn embedded_mismatch() {
+        let file = FileHandle::from(std::path::PathBuf::from("level.bin"));
+
+        let mut contents = Checksum::Embedded.embed(b"level".to_vec());
+        contents[0] ^= 1;
+        assert!(matches!(
+            Checksum::Embedded.verify(&file, contents),
+            Err(LoadError::ChecksumMismatch)
+        ));
+
+        assert!(matches!(
+            Checksum::Embedded.verify(&file, b"short".to_vec()),
+            Err(LoadError::ChecksumMismatch)
+        ));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn sidecar() {
+        let path = std::env::temp_dir().join("bevy_file_dialog_checksum.bin");
+        let file = FileHandle::from(path.clone());
+        let _ = std::fs::remove_file(sidecar_path(&file));
+
+        assert!(matches!(
+            Checksum::Sidecar.verify(&file, b"level".to_vec()),
+            Err(LoadError::MissingChecksum(_))
+        ));
+
+        Checksum::Sidecar.write_sidecar(&file, b"level").unwrap();
+        let sidecar = std::fs::read_to_string(sidecar_path(&file)).unwrap();
+        assert!(sidecar.ends_with("  bevy_file_dialog_checksum.bin\n"));
+
+        assert_eq!(
+            Checksum::Sidecar.verify(&file, b"level".to_vec()).unwrap(),
+            b"level"
+        );
+        assert!(matches!(
+            Checksum::Sidecar.verify(&file, b"levels".to_vec()),
+            Err(LoadError::ChecksumMismatch)
+        ));
+    }
+}
@@ -0,0 +1,457 @@
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+use bevy_ecs::prelude::*;
+use rfd::FileHandle;
+
+use crate::format::{FileFormat, VersionedFormat};
+use crate::ContentType;
+
+/// Compression of file contents, see [`FileDialog::set_compression`](crate::FileDialog::set_compression).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    /// Contents are loaded and saved as they are.
+    #[default]
+    None,
+
+    /// Compression is chosen by extension of the picked file, `.gz` files
+    /// use `Compression::Gzip` and `.zst` files use `Compression::Zstd`
+    /// when the corresponding feature is enabled, other files are not
+    /// compressed.
+    FromExtension,
+
+    /// Gzip compression, requires `gzip` feature.
+    #[cfg(feature = "gzip")]
+    Gzip,
+
+    /// Zstandard compression, requires `zstd` feature.
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    /// Resolve [`Compression::FromExtension`] for file with `file_name`.
+    fn resolve(self, file_name: &str) -> Self {
+        if self != Compression::FromExtension {
+            return self;
+        }
+
+        let extension = file_name.rsplit_once('.').map(|(_, ext)| ext);
+
+        match extension {
+            #[cfg(feature = "gzip")]
+            Some(ext) if ext.eq_ignore_ascii_case("gz") => Compression::Gzip,
+            #[cfg(feature = "zstd")]
+            Some(ext) if ext.eq_ignore_ascii_case("zst") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+
+    /// Extension of files compressed with this compression.
+    fn extension(self) -> Option<&'static str> {
+        match self {
+            Compression::None | Compression::FromExtension => None,
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => Some("gz"),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Some("zst"),
+        }
+    }
+
+    fn compress(self, contents: Vec<u8>) -> io::Result<Vec<u8>> {
+        match self {
+            Compression::None | Compression::FromExtension => Ok(contents),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => {
+                use std::io::Write;
+
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(&contents)?;
+                encoder.finish()
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => zstd::encode_all(contents.as_slice(), 0),
+        }
+    }
+
+    /// Decompress `contents`, or return number of bytes decompressed before
+    /// exceeding `max_size`.
+    #[cfg_attr(not(any(feature = "gzip", feature = "zstd")), allow(unused_variables))]
+    fn decompress(
+        self,
+        contents: Vec<u8>,
+        max_size: Option<u64>,
+    ) -> io::Result<Result<Vec<u8>, u64>> {
+        match self {
+            Compression::None | Compression::FromExtension => Ok(Ok(contents)),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => read_to_limit(
+                flate2::read::MultiGzDecoder::new(contents.as_slice()),
+                max_size,
+            ),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => read_to_limit(zstd::Decoder::new(contents.as_slice())?, max_size),
+        }
+    }
+}
+
+/// Read all of `reader`, or return number of bytes read before exceeding
+/// `max_size`. Stops reading as soon as the limit is exceeded, so small
+/// compressed file can't expand into arbitrarily large buffer.
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+fn read_to_limit(reader: impl io::Read, max_size: Option<u64>) -> io::Result<Result<Vec<u8>, u64>> {
+    use std::io::Read;
+
+    let mut contents = Vec::new();
+    let limit = max_size.map_or(u64::MAX, |max_size| max_size.saturating_add(1));
+    reader.take(limit).read_to_end(&mut contents)?;
+
+    match max_size {
+        Some(max_size) if contents.len() as u64 > max_size => Ok(Err(contents.len() as u64)),
+        _ => Ok(Ok(contents)),
+    }
+}
+
+/// Error that occurred when loaded file contents could not be decoded, sent
+/// in [`DialogFileLoadFailed`](crate::DialogFileLoadFailed).
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum LoadError {
+    /// File could not be read.
+    Read(io::Error),
+
+    /// File contents could not be decompressed.
+    Decompress(io::Error),
+
+    /// File contents could not be decrypted, either the password is wrong or
+    /// the file was tampered with.
+    Decrypt,
+
+    /// Password was set, but the file is not encrypted.
+    NotEncrypted,
+
+    /// File was encrypted with unsupported version of the format.
+    UnsupportedEncryption(u8),
+
+    /// File does not start with header of the
+    /// [`VersionedFormat`] registered for the marker.
+    InvalidHeader,
+
+    /// File was saved with newer version of [`VersionedFormat`] than the
+    /// current one.
+    UnsupportedVersion {
+        /// Version the file was saved with.
+        version: u32,
+        /// Current version of the format.
+        current: u32,
+    },
+
+    /// There is no migration registered from this version of
+    /// [`VersionedFormat`].
+    MissingMigration(u32),
+
+    /// Checksum stored with the file does not match its contents, the file
+    /// is corrupted.
+    ChecksumMismatch,
+
+    /// Checksum sidecar file could not be read.
+    MissingChecksum(io::Error),
+
+    /// File contents don't match its extension, see
+    /// [`FileDialog::set_verify_content`](crate::FileDialog::set_verify_content).
+    ContentMismatch {
+        /// Extension of the file.
+        extension: String,
+        /// Type detected from the contents, `None` if it was not recognized.
+        detected: Option<ContentType>,
+    },
+
+    /// Migration of [`VersionedFormat`] payload failed.
+    Migration {
+        /// Version the failed migration upgrades from.
+        from: u32,
+        /// Error returned from the migration.
+        message: String,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Read(err) => write!(f, "failed to read file: {err}"),
+            LoadError::Decompress(err) => write!(f, "failed to decompress file: {err}"),
+            LoadError::Decrypt => write!(f, "wrong password or file was tampered with"),
+            LoadError::NotEncrypted => write!(f, "file is not encrypted"),
+            LoadError::UnsupportedEncryption(version) => {
+                write!(f, "unsupported encryption format version {version}")
+            }
+            LoadError::InvalidHeader => write!(f, "file has invalid header"),
+            LoadError::ChecksumMismatch => write!(f, "checksum mismatch, file is corrupted"),
+            LoadError::MissingChecksum(err) => write!(f, "failed to read checksum: {err}"),
+            LoadError::UnsupportedVersion { version, current } => write!(
+                f,
+                "file version {version} is newer than supported version {current}"
+            ),
+            LoadError::MissingMigration(version) => {
+                write!(f, "no migration from file version {version}")
+            }
+            LoadError::ContentMismatch {
+                extension,
+                detected: Some(detected),
+            } => write!(
+                f,
+                "file with extension .{extension} contains {}",
+                detected.mime_type()
+            ),
+            LoadError::ContentMismatch {
+                extension,
+                detected: None,
+            } => write!(
+                f,
+                "file with extension .{extension} has unrecognized contents"
+            ),
+            LoadError::Migration { from, message } => {
+                write!(f, "failed to migrate file from version {from}: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Read(err) | LoadError::Decompress(err) | LoadError::MissingChecksum(err) => {
+                Some(err)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Transformations applied to file contents before they are saved and after
+/// they are loaded.
+#[derive(Clone, Default)]
+pub(crate) struct Codec {
+    pub(crate) compression: Compression,
+
+    pub(crate) format: Option<Arc<VersionedFormat>>,
+
+    #[cfg(feature = "encryption")]
+    pub(crate) password: Option<String>,
+
+    #[cfg(feature = "checksum")]
+    pub(crate) checksum: crate::Checksum,
+}
+
+impl Codec {
+    /// Use [`VersionedFormat`] registered for marker `T`, if there is any.
+    pub(crate) fn with_format<T: Send + Sync + 'static>(mut self, world: &World) -> Self {
+        self.format = world
+            .get_resource::<FileFormat<T>>()
+            .map(|format| format.0.clone());
+        self
+    }
+
+    /// Transform `contents` before they are saved to `file`.
+    pub(crate) fn encode(&self, file: &FileHandle, contents: Vec<u8>) -> io::Result<Vec<u8>> {
+        let contents = match &self.format {
+            Some(format) => format.add_header(contents),
+            None => contents,
+        };
+
+        let contents = self
+            .compression
+            .resolve(&file.file_name())
+            .compress(contents)?;
+
+        #[cfg(feature = "encryption")]
+        let contents = match &self.password {
+            Some(password) => crate::encryption::encrypt(password, &contents)?,
+            None => contents,
+        };
+
+        #[cfg(feature = "checksum")]
+        let contents = self.checksum.embed(contents);
+
+        Ok(contents)
+    }
+
+    /// Name of file with `file_name` without extension of the compression,
+    /// which no longer applies to decoded contents, `level.json.gz` becomes
+    /// `level.json`.
+    pub(crate) fn decoded_name<'a>(&self, file_name: &'a str) -> &'a str {
+        let extension = self.compression.resolve(file_name).extension();
+
+        match file_name.rsplit_once('.') {
+            Some((name, ext)) if extension.is_some_and(|e| e.eq_ignore_ascii_case(ext)) => name,
+            _ => file_name,
+        }
+    }
+
+    /// Transform `contents` loaded from `file`, reversing [`Codec::encode`].
+    /// When decompressed contents exceed `max_size`, decompression stops and
+    /// number of bytes decompressed so far is returned instead.
+    pub(crate) fn decode(
+        &self,
+        file: &FileHandle,
+        contents: Vec<u8>,
+        max_size: Option<u64>,
+    ) -> Result<Result<Vec<u8>, u64>, LoadError> {
+        #[cfg(feature = "checksum")]
+        let contents = self.checksum.verify(file, contents)?;
+
+        #[cfg(feature = "encryption")]
+        let contents = match &self.password {
+            Some(password) => crate::encryption::decrypt(password, &contents)?,
+            None => contents,
+        };
+
+        let decompressed = self
+            .compression
+            .resolve(&file.file_name())
+            .decompress(contents, max_size)
+            .map_err(LoadError::Decompress)?;
+
+        let contents = match decompressed {
+            Ok(contents) => contents,
+            Err(size) => return Ok(Err(size)),
+        };
+
+        match &self.format {
+            Some(format) => format.migrate(contents).map(Ok),
+            None => Ok(Ok(contents)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str) -> FileHandle {
+        FileHandle::from(std::path::PathBuf::from(name))
+    }
+
+    fn decoded(codec: &Codec, file: &FileHandle, contents: Vec<u8>) -> Vec<u8> {
+        codec
+            .decode(file, contents, None)
+            .unwrap()
+            .expect("contents are not limited")
+    }
+
+    #[test]
+    fn plain_round_trip() {
+        let codec = Codec::default();
+        let file = file("level.bin");
+
+        let encoded = codec.encode(&file, b"level".to_vec()).unwrap();
+        assert_eq!(encoded, b"level");
+        assert_eq!(decoded(&codec, &file, encoded), b"level");
+    }
+
+    #[test]
+    fn decoded_name() {
+        let codec = Codec::default();
+        assert_eq!(codec.decoded_name("level.json.gz"), "level.json.gz");
+
+        #[cfg(feature = "gzip")]
+        {
+            let codec = Codec {
+                compression: Compression::FromExtension,
+                ..Default::default()
+            };
+            assert_eq!(codec.decoded_name("level.json.GZ"), "level.json");
+            assert_eq!(codec.decoded_name("level.json"), "level.json");
+
+            let codec = Codec {
+                compression: Compression::Gzip,
+                ..Default::default()
+            };
+            assert_eq!(codec.decoded_name("level.gz"), "level");
+            assert_eq!(codec.decoded_name("level.json"), "level.json");
+        }
+    }
+
+    #[test]
+    fn compression_from_extension() {
+        assert_eq!(
+            Compression::FromExtension.resolve("level.bin"),
+            Compression::None
+        );
+        assert_eq!(
+            Compression::FromExtension.resolve("level"),
+            Compression::None
+        );
+        #[cfg(feature = "gzip")]
+        assert_eq!(
+            Compression::FromExtension.resolve("level.GZ"),
+            Compression::Gzip
+        );
+        #[cfg(feature = "zstd")]
+        assert_eq!(
+            Compression::FromExtension.resolve("level.zst"),
+            Compression::Zstd
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzip_round_trip() {
+        let codec = Codec {
+            compression: Compression::Gzip,
+            ..Default::default()
+        };
+        let file = file("level.bin");
+        let contents = b"level ".repeat(100);
+
+        let encoded = codec.encode(&file, contents.clone()).unwrap();
+        assert_ne!(encoded, contents);
+        assert_eq!(decoded(&codec, &file, encoded), contents);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn zstd_round_trip() {
+        let codec = Codec {
+            compression: Compression::FromExtension,
+            ..Default::default()
+        };
+        let file = file("level.zst");
+        let contents = b"level ".repeat(100);
+
+        let encoded = codec.encode(&file, contents.clone()).unwrap();
+        assert_ne!(encoded, contents);
+        assert_eq!(decoded(&codec, &file, encoded), contents);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn decompression_limited() {
+        let codec = Codec {
+            compression: Compression::Gzip,
+            ..Default::default()
+        };
+        let file = file("level.bin");
+        let encoded = codec.encode(&file, vec![0; 10_000]).unwrap();
+
+        let limited = codec.decode(&file, encoded.clone(), Some(100)).unwrap();
+        assert_eq!(limited, Err(101));
+
+        let exact = codec.decode(&file, encoded, Some(10_000)).unwrap();
+        assert_eq!(exact.map(|contents| contents.len()), Ok(10_000));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn invalid_compressed_data() {
+        let codec = Codec {
+            compression: Compression::Gzip,
+            ..Default::default()
+        };
+
+        let result = codec.decode(&file("level.bin"), b"not gzip".to_vec(), None);
+        assert!(matches!(result, Err(LoadError::Decompress(_))));
+    }
+}
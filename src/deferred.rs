@@ -0,0 +1,246 @@
+use std::marker::PhantomData;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_tasks::prelude::*;
+use crossbeam_channel::bounded;
+
+use crate::{
+    write_encoded, AppWaker, DialogFileSaved, DialogResult, FileDialog, SaveContents,
+    StreamReceiver, StreamSender, WakeUpOnDrop,
+};
+
+type Serialize = Box<dyn FnOnce(&mut World) -> Vec<u8> + Send + 'static>;
+
+/// Request to produce contents of a file that was picked in save dialog from
+/// the [`World`], the contents are sent back to the dialog task.
+struct PendingSave<T: SaveContents> {
+    serialize: Serialize,
+    reply: async_channel::Sender<Vec<u8>>,
+    marker: PhantomData<T>,
+}
+
+pub(crate) fn register_deferred_save<T: SaveContents>(app: &mut App) {
+    let (tx, rx) = bounded::<PendingSave<T>>(1);
+    app.insert_resource(StreamSender(tx));
+    app.insert_resource(StreamReceiver(rx));
+    app.add_systems(First, handle_pending_save::<T>);
+}
+
+fn handle_pending_save<T: SaveContents>(world: &mut World) {
+    let pending: Vec<_> = world
+        .resource::<StreamReceiver<PendingSave<T>>>()
+        .try_iter()
+        .collect();
+
+    for pending in pending {
+        let contents = (pending.serialize)(world);
+        // The dialog task is waiting for the reply, it can only be gone when
+        // the task pool is shutting down.
+        let _ = pending.reply.try_send(contents);
+    }
+}
+
+impl FileDialog<'_, '_, '_> {
+    /// Open save file dialog and save contents returned by `serialize` to
+    /// that file. The `serialize` runs on [`AsyncComputeTaskPool`] only after
+    /// the user picks the file, when the dialog is canceled, it is not called
+    /// at all. When file gets saved, the [`DialogFileSaved<T>`] gets sent.
+    pub fn save_file_with<T: SaveContents>(
+        self,
+        serialize: impl FnOnce() -> Vec<u8> + Send + 'static,
+    ) {
+        self.commands.queue(move |world: &mut World| {
+            let sender = world
+                .get_resource::<StreamSender<DialogResult<DialogFileSaved<T>>>>()
+                .expect("FileDialogPlugin not initialized with 'with_save_file::<T>()'")
+                .0
+                .clone();
+
+            let waker = AppWaker::from_world(world);
+
+            let codec = self.codec.with_format::<T>(world);
+
+            let dialog = self.config.apply::<T>(self.dialog, world);
+
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    let file = dialog.save_file().await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
+
+                    let Some(file) = file else {
+                        sender.send(DialogResult::Canceled).unwrap();
+                        return;
+                    };
+
+                    let contents = serialize();
+
+                    let event = DialogFileSaved {
+                        file_name: file.file_name(),
+                        result: write_encoded(&file, &codec, contents).await,
+                        #[cfg(not(target_arch = "wasm32"))]
+                        path: file.path().to_path_buf(),
+                        marker: PhantomData,
+                    };
+
+                    sender.send(DialogResult::Single(event)).unwrap();
+                })
+                .detach();
+        });
+    }
+
+    /// Open save file dialog and save contents returned by `serialize` to
+    /// that file. The `serialize` gets exclusive access to the [`World`] and
+    /// runs in [`First`] schedule only after the user picks the file, when the
+    /// dialog is canceled, it is not called at all. When file gets saved, the
+    /// [`DialogFileSaved<T>`] gets sent.
+    ///
+    /// The contents are produced from the state of the world at the time the
+    /// file was picked, not when the dialog was opened.
+    pub fn save_file_from_world<T: SaveContents>(
+        self,
+        serialize: impl FnOnce(&mut World) -> Vec<u8> + Send + 'static,
+    ) {
+        self.commands.queue(move |world: &mut World| {
+            let sender = world
+                .get_resource::<StreamSender<DialogResult<DialogFileSaved<T>>>>()
+                .expect("FileDialogPlugin not initialized with 'with_save_file::<T>()'")
+                .0
+                .clone();
+
+            let pending_sender = world
+                .get_resource::<StreamSender<PendingSave<T>>>()
+                .expect("FileDialogPlugin not initialized with 'with_save_file::<T>()'")
+                .0
+                .clone();
+
+            let waker = AppWaker::from_world(world);
+
+            let codec = self.codec.with_format::<T>(world);
+
+            let dialog = self.config.apply::<T>(self.dialog, world);
+
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    let file = dialog.save_file().await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
+
+                    let Some(file) = file else {
+                        sender.send(DialogResult::Canceled).unwrap();
+                        return;
+                    };
+
+                    let (reply, contents) = async_channel::bounded(1);
+                    let pending = PendingSave {
+                        serialize: Box::new(serialize),
+                        reply,
+                        marker: PhantomData,
+                    };
+
+                    pending_sender.send(pending).unwrap();
+                    // Wake up the app so it can produce the contents right away.
+                    drop(waker.as_ref().map(WakeUpOnDrop));
+
+                    let Ok(contents) = contents.recv().await else {
+                        return;
+                    };
+
+                    let event = DialogFileSaved {
+                        file_name: file.file_name(),
+                        result: write_encoded(&file, &codec, contents).await,
+                        #[cfg(not(target_arch = "wasm32"))]
+                        path: file.path().to_path_buf(),
+                        marker: PhantomData,
+                    };
+
+                    sender.send(DialogResult::Single(event)).unwrap();
+                })
+                .detach();
+        });
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::testing;
+    use crate::{DialogFileSaveCanceled, FileDialogExt, FileDialogPlugin};
+
+    struct Deferred;
+
+    #[derive(Resource)]
+    struct Score(u32);
+
+    fn app(name: &str) -> (App, PathBuf) {
+        let path = testing::test_directory(name).join("save.txt");
+        let mut app = testing::app(FileDialogPlugin::new().with_save_file::<Deferred>());
+        testing::collect::<DialogFileSaved<Deferred>>(&mut app);
+        testing::collect::<DialogFileSaveCanceled<Deferred>>(&mut app);
+        (app, path)
+    }
+
+    #[test]
+    fn save_with() {
+        let (mut app, path) = app("save_with");
+        testing::respond(&mut app, [&path]);
+
+        app.world_mut()
+            .commands()
+            .dialog()
+            .save_file_with::<Deferred>(|| b"serialized".to_vec());
+        testing::update_until_received::<DialogFileSaved<Deferred>>(&mut app);
+
+        assert!(testing::received::<DialogFileSaved<Deferred>>(&app)[0]
+            .result
+            .is_ok());
+        assert_eq!(fs::read(&path).unwrap(), b"serialized");
+    }
+
+    #[test]
+    fn save_with_canceled() {
+        let (mut app, _) = app("save_with_canceled");
+        testing::respond::<PathBuf>(&mut app, []);
+
+        let called = Arc::new(AtomicBool::new(false));
+        let serialize_called = called.clone();
+        app.world_mut()
+            .commands()
+            .dialog()
+            .save_file_with::<Deferred>(move || {
+                serialize_called.store(true, Ordering::Relaxed);
+                Vec::new()
+            });
+        testing::update_until_received::<DialogFileSaveCanceled<Deferred>>(&mut app);
+
+        assert!(!called.load(Ordering::Relaxed));
+        assert!(testing::received::<DialogFileSaved<Deferred>>(&app).is_empty());
+    }
+
+    #[test]
+    fn save_from_world() {
+        let (mut app, path) = app("save_from_world");
+        testing::respond(&mut app, [&path]);
+        app.insert_resource(Score(1));
+
+        app.world_mut()
+            .commands()
+            .dialog()
+            .save_file_from_world::<Deferred>(|world| {
+                world.resource::<Score>().0.to_string().into_bytes()
+            });
+        // Contents are produced from the world when the file is picked
+        app.world_mut().flush();
+        app.insert_resource(Score(2));
+        testing::update_until_received::<DialogFileSaved<Deferred>>(&mut app);
+
+        assert!(testing::received::<DialogFileSaved<Deferred>>(&app)[0]
+            .result
+            .is_ok());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "2");
+    }
+}
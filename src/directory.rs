@@ -0,0 +1,393 @@
+use std::fs;
+use std::io;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_tasks::prelude::*;
+use crossbeam_channel::bounded;
+use rfd::FileHandle;
+
+use crate::codec::Codec;
+use crate::parallel::load_concurrently;
+use crate::{
+    decode_loaded, handle_message, AppWaker, DialogFileLoadFailed, DialogFileLoaded,
+    DialogFileTooLarge, DialogOutcome, DialogResult, FileDialog, LoadContents, MaxFileSize,
+    StreamReceiver, StreamSender, WakeUpOnDrop,
+};
+
+/// Event that gets sent after all files from directory picked with
+/// [`FileDialog::load_directory`] are read. It is sent in the same frame as
+/// the batch of [`DialogFileLoaded<T>`] events for the loaded files.
+#[derive(Message)]
+pub struct DialogDirectoryLoaded<T: LoadContents> {
+    /// Path of picked directory.
+    pub path: PathBuf,
+
+    /// Number of files that were successfully loaded.
+    pub loaded: usize,
+
+    /// Number of files that were skipped, because they did not match any of
+    /// the extensions added with [`FileDialog::add_filter`].
+    pub skipped: usize,
+
+    /// Number of files that were not loaded because they exceeded maximum
+    /// file size, [`DialogFileTooLarge<T>`] is sent for each of them.
+    pub too_large: usize,
+
+    /// Number of files that were read but could not be decoded,
+    /// [`DialogFileLoadFailed<T>`] is sent for each of them.
+    pub failed: usize,
+
+    /// Files that matched the filter but could not be read, along with the
+    /// error that occurred.
+    pub errors: Vec<(PathBuf, io::Error)>,
+
+    marker: PhantomData<T>,
+}
+
+pub(crate) fn register_load_directory<T: LoadContents>(app: &mut App) {
+    let (tx, rx) = bounded::<DialogDirectoryLoaded<T>>(1);
+    app.insert_resource(StreamSender(tx));
+    app.insert_resource(StreamReceiver(rx));
+    app.add_message::<DialogDirectoryLoaded<T>>();
+    app.add_systems(First, handle_message::<DialogDirectoryLoaded<T>>);
+}
+
+impl FileDialog<'_, '_, '_> {
+    /// Open pick directory dialog and load contents of all files inside the
+    /// picked directory. Only files with extensions added with
+    /// [`FileDialog::add_filter`] or `FileDialog::add_asset_filter` are
+    /// loaded, if there are no filters, all files are loaded. The files are
+    /// read the same way as with [`FileDialog::load_multiple_files`], see
+    /// [`FileDialog::set_max_concurrent_loads`], and the
+    /// [`DialogFileLoaded<T>`] gets sent for each file as a batch, followed by
+    /// [`DialogDirectoryLoaded<T>`] with summary of the whole load.
+    ///
+    /// Each file is decoded the same way as with [`FileDialog::load_file`],
+    /// so [`FileDialog::set_compression`], [`FileDialog::set_verify_content`]
+    /// and similar functions apply to every loaded file. Symbolic links to
+    /// directories are not followed.
+    ///
+    /// Does not exist in `wasm32`.
+    pub fn load_directory<T: LoadContents>(self) {
+        self.load_directory_impl::<T>(false);
+    }
+
+    /// Same as [`FileDialog::load_directory`], but also loads files from all
+    /// subdirectories of the picked directory.
+    ///
+    /// Does not exist in `wasm32`.
+    pub fn load_directory_recursive<T: LoadContents>(self) {
+        self.load_directory_impl::<T>(true);
+    }
+
+    fn load_directory_impl<T: LoadContents>(self, recursive: bool) {
+        self.commands.queue(move |world: &mut World| {
+            let sender = world
+                .get_resource::<StreamSender<DialogResult<DialogFileLoaded<T>>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
+
+            let summary_sender = world
+                .get_resource::<StreamSender<DialogDirectoryLoaded<T>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
+
+            let too_large_sender = world
+                .get_resource::<StreamSender<DialogFileTooLarge<T>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
+
+            let failed_sender = world
+                .get_resource::<StreamSender<DialogFileLoadFailed<T>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
+
+            let max_size = self.load_options.max_size.or_else(|| {
+                world
+                    .get_resource::<MaxFileSize<T>>()
+                    .map(|max_size| max_size.0)
+            });
+
+            let waker = AppWaker::from_world(world);
+
+            let codec = self.codec.with_format::<T>(world);
+            let verify_content = self.load_options.verify_content;
+            let collect_metadata = self.collect_metadata;
+
+            let extensions = self.config.extensions::<T>(self.extensions, world);
+            let dialog = self.config.apply::<T>(self.dialog, world);
+
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    let directory = dialog.pick_folder().await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
+
+                    let Some(directory) = directory else {
+                        sender.send(DialogResult::Canceled).unwrap();
+                        return;
+                    };
+
+                    let path = directory.path().to_path_buf();
+                    let mut files = Vec::new();
+                    let mut errors = Vec::new();
+                    collect_files(&path, recursive, &mut files, &mut errors);
+
+                    let total = files.len();
+                    files.retain(|file| matches_extensions(file, &extensions));
+                    let skipped = total - files.len();
+
+                    let files = files.into_iter().map(FileHandle::from).collect();
+                    let mut loaded = Vec::new();
+                    let mut too_large = 0;
+                    let mut failed = 0;
+                    load_concurrently(
+                        files,
+                        self.load_options.max_concurrent_loads,
+                        move |file| {
+                            let codec = codec.clone();
+                            async move {
+                                load_file::<T>(
+                                    file.path().to_path_buf(),
+                                    max_size,
+                                    verify_content,
+                                    &codec,
+                                    collect_metadata,
+                                )
+                            }
+                        },
+                        |index, outcome| match outcome {
+                            Ok(DialogOutcome::Loaded(event)) => loaded.push((index, event)),
+                            Ok(DialogOutcome::TooLarge(event)) => {
+                                too_large += 1;
+                                too_large_sender.send(event).unwrap();
+                            }
+                            Ok(DialogOutcome::Failed(event)) => {
+                                failed += 1;
+                                failed_sender.send(event).unwrap();
+                            }
+                            Ok(_) => {}
+                            Err(error) => errors.push(error),
+                        },
+                    )
+                    .await;
+
+                    // Files are loaded in any order, send them in the order they were collected
+                    loaded.sort_by_key(|(index, _)| *index);
+                    let events: Vec<_> = loaded.into_iter().map(|(_, event)| event).collect();
+
+                    let summary = DialogDirectoryLoaded {
+                        path,
+                        loaded: events.len(),
+                        skipped,
+                        too_large,
+                        failed,
+                        errors,
+                        marker: PhantomData,
+                    };
+
+                    sender.send(DialogResult::Batch(events)).unwrap();
+                    summary_sender.send(summary).unwrap();
+                })
+                .detach();
+        });
+    }
+}
+
+fn collect_files(
+    directory: &Path,
+    recursive: bool,
+    files: &mut Vec<PathBuf>,
+    errors: &mut Vec<(PathBuf, io::Error)>,
+) {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(err) => {
+            errors.push((directory.to_path_buf(), err));
+            return;
+        }
+    };
+
+    let mut entries: Vec<_> = entries
+        .filter_map(|entry| match entry {
+            Ok(entry) => Some(entry),
+            Err(err) => {
+                errors.push((directory.to_path_buf(), err));
+                None
+            }
+        })
+        .collect();
+
+    // Directory entries come in unspecified order
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+
+        let file_type = match entry.file_type() {
+            Ok(file_type) => file_type,
+            Err(err) => {
+                errors.push((path, err));
+                continue;
+            }
+        };
+
+        if file_type.is_dir() {
+            if recursive {
+                collect_files(&path, recursive, files, errors);
+            }
+        } else if file_type.is_symlink() && path.is_dir() {
+            // Linked directory may contain its ancestor, following it could
+            // recurse forever
+            continue;
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// Read file at `path` unless it is larger than `max_size`, and decode its
+/// contents with `codec`.
+fn load_file<T: LoadContents>(
+    path: PathBuf,
+    max_size: Option<u64>,
+    verify_content: bool,
+    codec: &Codec,
+    collect_metadata: bool,
+) -> Result<DialogOutcome<T>, (PathBuf, io::Error)> {
+    let file = FileHandle::from(path.clone());
+
+    if let Some(max_size) = max_size {
+        let size = fs::metadata(&path)
+            .map_err(|err| (path.clone(), err))?
+            .len();
+        if let Err(event) = DialogFileTooLarge::check(&file, size, Some(max_size)) {
+            return Ok(DialogOutcome::TooLarge(event));
+        }
+    }
+
+    let contents = fs::read(&path).map_err(|err| (path, err))?;
+
+    Ok(decode_loaded(
+        &file,
+        contents,
+        max_size,
+        verify_content,
+        codec,
+        collect_metadata,
+    ))
+}
+
+fn matches_extensions(path: &Path, extensions: &[String]) -> bool {
+    if extensions.is_empty() || extensions.iter().any(|ext| ext == "*") {
+        return true;
+    }
+
+    let Some(extension) = path.extension().and_then(|ext| ext.to_str()) else {
+        return false;
+    };
+
+    extensions
+        .iter()
+        .any(|ext| ext.eq_ignore_ascii_case(extension))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, test_directory};
+    use crate::{FileDialogExt, FileDialogPlugin};
+
+    struct Directory;
+
+    #[test]
+    fn collect_files_sorted() {
+        let directory = test_directory("collect_sorted");
+        fs::create_dir(directory.join("sub")).unwrap();
+        fs::write(directory.join("b.txt"), b"b").unwrap();
+        fs::write(directory.join("a.txt"), b"a").unwrap();
+        fs::write(directory.join("sub").join("c.txt"), b"c").unwrap();
+
+        let mut files = Vec::new();
+        let mut errors = Vec::new();
+        collect_files(&directory, true, &mut files, &mut errors);
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            files,
+            [
+                directory.join("a.txt"),
+                directory.join("b.txt"),
+                directory.join("sub").join("c.txt"),
+            ]
+        );
+
+        let mut files = Vec::new();
+        collect_files(&directory, false, &mut files, &mut errors);
+        assert_eq!(files, [directory.join("a.txt"), directory.join("b.txt")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn collect_files_skips_linked_directories() {
+        let directory = test_directory("collect_links");
+        fs::write(directory.join("a.txt"), b"a").unwrap();
+        std::os::unix::fs::symlink(&directory, directory.join("loop")).unwrap();
+        std::os::unix::fs::symlink(directory.join("a.txt"), directory.join("link.txt")).unwrap();
+
+        let mut files = Vec::new();
+        let mut errors = Vec::new();
+        collect_files(&directory, true, &mut files, &mut errors);
+
+        assert!(errors.is_empty());
+        assert_eq!(files, [directory.join("a.txt"), directory.join("link.txt")]);
+    }
+
+    #[test]
+    fn extensions() {
+        let extensions = ["png".to_string()];
+        assert!(matches_extensions(Path::new("a.PNG"), &extensions));
+        assert!(!matches_extensions(Path::new("a.jpg"), &extensions));
+        assert!(!matches_extensions(Path::new("png"), &extensions));
+        assert!(matches_extensions(Path::new("a.jpg"), &[]));
+        assert!(matches_extensions(Path::new("a"), &["*".to_string()]));
+    }
+
+    #[test]
+    fn load_directory_concurrently() {
+        let directory = test_directory("load_directory");
+        for name in ["a.txt", "b.txt", "c.txt", "d.png"] {
+            fs::write(directory.join(name), name).unwrap();
+        }
+
+        let mut app = testing::app(FileDialogPlugin::new().with_load_file::<Directory>());
+        testing::collect::<DialogFileLoaded<Directory>>(&mut app);
+        testing::collect::<DialogDirectoryLoaded<Directory>>(&mut app);
+        testing::respond(&mut app, [&directory]);
+
+        app.world_mut()
+            .commands()
+            .dialog()
+            .add_filter("Text", &["txt"])
+            .set_max_concurrent_loads(2)
+            .load_directory::<Directory>();
+        testing::update_until_received::<DialogDirectoryLoaded<Directory>>(&mut app);
+
+        let loaded: Vec<_> = testing::received::<DialogFileLoaded<Directory>>(&app)
+            .iter()
+            .map(|event| event.file_name.as_str())
+            .collect();
+        assert_eq!(loaded, ["a.txt", "b.txt", "c.txt"]);
+
+        let summary = &testing::received::<DialogDirectoryLoaded<Directory>>(&app)[0];
+        assert_eq!(summary.path, directory);
+        assert_eq!(summary.loaded, 3);
+        assert_eq!(summary.skipped, 1);
+    }
+}
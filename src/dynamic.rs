@@ -0,0 +1,458 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_tasks::prelude::*;
+use crossbeam_channel::bounded;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::FileMetadata;
+use crate::{
+    handle_message, load_picked_file, write_encoded, AppWaker, ContentType, DialogOutcome,
+    FileDialog, FileDialogPlugin, LoadError, StreamReceiver, StreamSender, WakeUpOnDrop,
+};
+
+/// Name of dialog kind created at runtime, used instead of marker type by
+/// [`FileDialog::load_file_dynamic`] and [`FileDialog::save_file_dynamic`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DialogKindId(Cow<'static, str>);
+
+impl DialogKindId {
+    /// Create dialog kind with `name`.
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self(name.into())
+    }
+
+    /// Name of the dialog kind.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&'static str> for DialogKindId {
+    fn from(name: &'static str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for DialogKindId {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl fmt::Display for DialogKindId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Event that gets sent when dialog opened with dynamic dialog kind
+/// completes. You can read this event with Bevy's
+/// [`MessageReader<DynamicDialogResult>`] and match on
+/// [`DynamicDialogResult::kind`] to find out which dialog it belongs to.
+#[derive(Message, Debug)]
+pub struct DynamicDialogResult {
+    /// Kind of the dialog passed when it was opened.
+    pub kind: DialogKindId,
+
+    /// What happened in the dialog.
+    pub outcome: DynamicDialogOutcome,
+}
+
+/// Outcome of dialog opened with dynamic dialog kind, see
+/// [`DynamicDialogResult`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DynamicDialogOutcome {
+    /// File contents were loaded, same as [`DialogFileLoaded`](crate::DialogFileLoaded).
+    Loaded {
+        /// Name of loaded file.
+        file_name: String,
+
+        /// Byte contents of loaded file.
+        contents: Vec<u8>,
+
+        /// Type of the file detected from its first bytes, present only when
+        /// [`FileDialog::set_verify_content`] is enabled and the type is
+        /// recognized.
+        content_type: Option<ContentType>,
+
+        /// Metadata of loaded file, present only when
+        /// [`FileDialog::set_collect_metadata`] is enabled.
+        ///
+        /// Does not exist in wasm, you can use this on native platforms only.
+        #[cfg(not(target_arch = "wasm32"))]
+        metadata: Option<FileMetadata>,
+
+        /// Path to loaded file.
+        ///
+        /// Does not exist in wasm, you can use this on native platforms only.
+        #[cfg(not(target_arch = "wasm32"))]
+        path: std::path::PathBuf,
+    },
+
+    /// File contents were saved, same as [`DialogFileSaved`](crate::DialogFileSaved).
+    Saved {
+        /// Name of saved file.
+        file_name: String,
+
+        /// Result of save file system operation.
+        result: io::Result<()>,
+
+        /// Path to saved file.
+        ///
+        /// Does not exist in wasm, you can use this on native platforms only.
+        #[cfg(not(target_arch = "wasm32"))]
+        path: std::path::PathBuf,
+    },
+
+    /// File path was picked, same as [`DialogFilePicked`](crate::DialogFilePicked).
+    ///
+    /// Does not exist in wasm, you can use this on native platforms only.
+    #[cfg(not(target_arch = "wasm32"))]
+    Picked {
+        /// Path of picked file.
+        path: std::path::PathBuf,
+    },
+
+    /// Picked file was larger than the limit set with
+    /// [`FileDialog::set_max_file_size`], same as
+    /// [`DialogFileTooLarge`](crate::DialogFileTooLarge).
+    TooLarge {
+        /// Name of picked file.
+        file_name: String,
+
+        /// Actual size of picked file in bytes.
+        size: u64,
+
+        /// Maximum allowed size in bytes.
+        max_size: u64,
+    },
+
+    /// Picked file could not be decoded, same as
+    /// [`DialogFileLoadFailed`](crate::DialogFileLoadFailed).
+    LoadFailed {
+        /// Name of picked file.
+        file_name: String,
+
+        /// Reason why the file could not be loaded.
+        error: LoadError,
+    },
+
+    /// User closed the dialog without picking any file.
+    Canceled,
+}
+
+impl<T: Send + Sync + 'static> From<DialogOutcome<T>> for DynamicDialogOutcome {
+    fn from(outcome: DialogOutcome<T>) -> Self {
+        match outcome {
+            DialogOutcome::Loaded(loaded) => Self::Loaded {
+                file_name: loaded.file_name,
+                contents: Arc::unwrap_or_clone(loaded.contents),
+                content_type: loaded.content_type,
+                #[cfg(not(target_arch = "wasm32"))]
+                metadata: loaded.metadata,
+                #[cfg(not(target_arch = "wasm32"))]
+                path: loaded.path,
+            },
+            DialogOutcome::Saved(saved) => Self::Saved {
+                file_name: saved.file_name,
+                result: saved.result,
+                #[cfg(not(target_arch = "wasm32"))]
+                path: saved.path,
+            },
+            #[cfg(not(target_arch = "wasm32"))]
+            DialogOutcome::Picked(picked) => Self::Picked { path: picked.path },
+            #[cfg(not(target_arch = "wasm32"))]
+            DialogOutcome::DirectoryPicked(picked) => Self::Picked { path: picked.path },
+            DialogOutcome::TooLarge(too_large) => Self::TooLarge {
+                file_name: too_large.file_name,
+                size: too_large.size,
+                max_size: too_large.max_size,
+            },
+            DialogOutcome::Failed(failed) => Self::LoadFailed {
+                file_name: failed.file_name,
+                error: failed.error,
+            },
+            DialogOutcome::Canceled => Self::Canceled,
+        }
+    }
+}
+
+pub(crate) fn register_dynamic_dialogs(app: &mut App) {
+    if app
+        .world()
+        .contains_resource::<StreamSender<DynamicDialogResult>>()
+    {
+        return;
+    }
+
+    let (tx, rx) = bounded::<DynamicDialogResult>(1);
+    app.insert_resource(StreamSender(tx));
+    app.insert_resource(StreamReceiver(rx));
+    app.add_message::<DynamicDialogResult>();
+    app.add_systems(First, handle_message::<DynamicDialogResult>);
+}
+
+impl FileDialogPlugin {
+    /// Allow opening dialogs with dynamic dialog kinds. This allows you to
+    /// call [`FileDialog::load_file_dynamic`],
+    /// [`FileDialog::save_file_dynamic`] and `pick_file_path_dynamic` on
+    /// [`Commands`] and you will receive [`DynamicDialogResult`] in your
+    /// systems when they complete.
+    ///
+    /// This is useful for scripting and mods, which can't create new marker
+    /// types.
+    pub fn with_dynamic_dialogs(mut self) -> Self {
+        self.0.push(Box::new(register_dynamic_dialogs));
+        self
+    }
+}
+
+impl FileDialog<'_, '_, '_> {
+    /// Open pick file dialog and load its contents. When file contents get
+    /// loaded, the [`DynamicDialogResult`] with `kind` gets sent. Loaded
+    /// contents are decoded the same way as with [`FileDialog::load_file`],
+    /// except for [`VersionedFormat`](crate::VersionedFormat), which is
+    /// registered per marker type.
+    pub fn load_file_dynamic(self, kind: impl Into<DialogKindId>) {
+        let kind = kind.into();
+
+        self.commands.queue(move |world: &mut World| {
+            let sender = world
+                .get_resource::<StreamSender<DynamicDialogResult>>()
+                .expect("FileDialogPlugin not initialized with 'with_dynamic_dialogs()'")
+                .0
+                .clone();
+
+            let waker = AppWaker::from_world(world);
+
+            let dialog = self.config.apply_asset_filters(self.dialog, world);
+
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    let file = dialog.pick_file().await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
+
+                    let outcome = match file {
+                        // Dynamic dialogs have no marker type, their kind is
+                        // used as the marker of the shared load outcome
+                        Some(file) => load_picked_file::<DialogKindId>(
+                            &file,
+                            self.load_options.max_size,
+                            self.load_options.verify_content,
+                            &self.codec,
+                            #[cfg(not(target_arch = "wasm32"))]
+                            self.collect_metadata,
+                        )
+                        .await
+                        .into(),
+                        None => DynamicDialogOutcome::Canceled,
+                    };
+
+                    sender.send(DynamicDialogResult { kind, outcome }).unwrap();
+                })
+                .detach();
+        });
+    }
+
+    /// Open save file dialog and save the `contents` to that file. When file
+    /// gets saved, the [`DynamicDialogResult`] with `kind` gets sent.
+    pub fn save_file_dynamic(self, kind: impl Into<DialogKindId>, contents: Vec<u8>) {
+        let kind = kind.into();
+
+        self.commands.queue(move |world: &mut World| {
+            let sender = world
+                .get_resource::<StreamSender<DynamicDialogResult>>()
+                .expect("FileDialogPlugin not initialized with 'with_dynamic_dialogs()'")
+                .0
+                .clone();
+
+            let waker = AppWaker::from_world(world);
+
+            let dialog = self.config.apply_asset_filters(self.dialog, world);
+
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    let file = dialog.save_file().await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
+
+                    let outcome = match file {
+                        None => DynamicDialogOutcome::Canceled,
+                        Some(file) => DynamicDialogOutcome::Saved {
+                            file_name: file.file_name(),
+                            result: write_encoded(&file, &self.codec, contents).await,
+                            #[cfg(not(target_arch = "wasm32"))]
+                            path: file.path().to_path_buf(),
+                        },
+                    };
+
+                    sender.send(DynamicDialogResult { kind, outcome }).unwrap();
+                })
+                .detach();
+        });
+    }
+
+    /// Open pick file dialog and send [`DynamicDialogResult`] with `kind` and
+    /// the picked path.
+    ///
+    /// Does not exist in `wasm32`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn pick_file_path_dynamic(self, kind: impl Into<DialogKindId>) {
+        let kind = kind.into();
+
+        self.commands.queue(move |world: &mut World| {
+            let sender = world
+                .get_resource::<StreamSender<DynamicDialogResult>>()
+                .expect("FileDialogPlugin not initialized with 'with_dynamic_dialogs()'")
+                .0
+                .clone();
+
+            let waker = AppWaker::from_world(world);
+
+            let dialog = self.config.apply_asset_filters(self.dialog, world);
+
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    let file = dialog.pick_file().await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
+
+                    let outcome = match file {
+                        Some(file) => DynamicDialogOutcome::Picked {
+                            path: file.path().to_path_buf(),
+                        },
+                        None => DynamicDialogOutcome::Canceled,
+                    };
+
+                    sender.send(DynamicDialogResult { kind, outcome }).unwrap();
+                })
+                .detach();
+        });
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::testing::{self, collect, received, respond, test_directory, update_until};
+    use crate::FileDialogExt;
+
+    fn outcome<'a>(app: &'a App, kind: &str) -> &'a DynamicDialogOutcome {
+        let results = received::<DynamicDialogResult>(app);
+        let mut matching = results.iter().filter(|result| result.kind.as_str() == kind);
+        let result = matching.next().expect("no result of the kind");
+        assert!(matching.next().is_none(), "more results of the kind");
+        &result.outcome
+    }
+
+    #[test]
+    fn results_routed_by_kind() {
+        let directory = test_directory("dynamic_routing");
+        let image = directory.join("image.png");
+        fs::write(&image, b"\x89PNG\r\n\x1a\n....").unwrap();
+        let large = directory.join("large.txt");
+        fs::write(&large, b"too large").unwrap();
+
+        let mut app = testing::app(FileDialogPlugin::new().with_dynamic_dialogs());
+        collect::<DynamicDialogResult>(&mut app);
+
+        respond(&mut app, [&image]);
+        respond(&mut app, [&large]);
+        respond(&mut app, [directory.join("saved.txt")]);
+        respond(&mut app, Vec::<&str>::new());
+        respond(&mut app, [&image]);
+
+        let mut commands = app.world_mut().commands();
+        commands
+            .dialog()
+            .set_verify_content(true)
+            .set_collect_metadata(true)
+            .load_file_dynamic("image");
+        commands
+            .dialog()
+            .set_max_file_size(4)
+            .load_file_dynamic("large");
+        commands
+            .dialog()
+            .save_file_dynamic("save", b"saved".to_vec());
+        commands.dialog().load_file_dynamic("canceled");
+        commands.dialog().pick_file_path_dynamic("pick");
+
+        update_until(&mut app, |world| {
+            world
+                .resource::<testing::Received<DynamicDialogResult>>()
+                .0
+                .len()
+                == 5
+        });
+
+        let DynamicDialogOutcome::Loaded {
+            file_name,
+            contents,
+            content_type,
+            metadata,
+            path,
+        } = outcome(&app, "image")
+        else {
+            panic!("image not loaded");
+        };
+        assert_eq!(file_name, "image.png");
+        assert_eq!(contents, b"\x89PNG\r\n\x1a\n....");
+        assert_eq!(*content_type, Some(ContentType::Png));
+        assert_eq!(metadata.as_ref().unwrap().size, 12);
+        assert_eq!(path, &image);
+
+        assert!(matches!(
+            outcome(&app, "large"),
+            DynamicDialogOutcome::TooLarge {
+                size: 9,
+                max_size: 4,
+                ..
+            }
+        ));
+
+        let DynamicDialogOutcome::Saved { result, .. } = outcome(&app, "save") else {
+            panic!("file not saved");
+        };
+        assert!(result.is_ok());
+        assert_eq!(fs::read(directory.join("saved.txt")).unwrap(), b"saved");
+
+        assert!(matches!(
+            outcome(&app, "canceled"),
+            DynamicDialogOutcome::Canceled
+        ));
+        assert!(matches!(
+            outcome(&app, "pick"),
+            DynamicDialogOutcome::Picked { path } if path == &image
+        ));
+    }
+
+    #[test]
+    fn load_failed() {
+        let directory = test_directory("dynamic_failed");
+        let image = directory.join("image.png");
+        fs::write(&image, b"not an image").unwrap();
+
+        let mut app = testing::app(FileDialogPlugin::new().with_dynamic_dialogs());
+        collect::<DynamicDialogResult>(&mut app);
+        respond(&mut app, [&image]);
+
+        app.world_mut()
+            .commands()
+            .dialog()
+            .set_verify_content(true)
+            .load_file_dynamic("image");
+        testing::update_until_received::<DynamicDialogResult>(&mut app);
+
+        assert!(matches!(
+            outcome(&app, "image"),
+            DynamicDialogOutcome::LoadFailed { file_name, .. } if file_name == "image.png"
+        ));
+    }
+}
@@ -0,0 +1,120 @@
+//! Password based encryption of file contents.
+//!
+//! Encrypted file starts with a header identifying the format, followed by
+//! the ciphertext:
+//!
+//! | bytes | contents                                     |
+//! | ----- | -------------------------------------------- |
+//! | 4     | magic `BFDE`                                 |
+//! | 1     | format version                               |
+//! | 16    | salt for deriving key from password (Argon2) |
+//! | 24    | nonce (XChaCha20-Poly1305)                   |
+//! | ..    | ciphertext with authentication tag           |
+
+use std::io;
+
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+
+use crate::LoadError;
+
+const MAGIC: &[u8; 4] = b"BFDE";
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const HEADER_LEN: usize = MAGIC.len() + 1 + SALT_LEN + NONCE_LEN;
+
+fn derive_key(password: &str, salt: &[u8]) -> Result<Key, argon2::Error> {
+    let mut key = Key::default();
+    Argon2::default().hash_password_into(password.as_bytes(), salt, &mut key)?;
+    Ok(key)
+}
+
+/// Encrypt `contents` with key derived from `password`.
+pub(crate) fn encrypt(password: &str, contents: &[u8]) -> io::Result<Vec<u8>> {
+    let mut salt = [0; SALT_LEN];
+    let mut nonce = [0; NONCE_LEN];
+    getrandom::fill(&mut salt).map_err(io::Error::other)?;
+    getrandom::fill(&mut nonce).map_err(io::Error::other)?;
+
+    let key = derive_key(password, &salt).map_err(io::Error::other)?;
+    let ciphertext = XChaCha20Poly1305::new(&key)
+        .encrypt(XNonce::from_slice(&nonce), contents)
+        .map_err(|_| io::Error::other("failed to encrypt file contents"))?;
+
+    let mut encrypted = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+    encrypted.extend_from_slice(MAGIC);
+    encrypted.push(VERSION);
+    encrypted.extend_from_slice(&salt);
+    encrypted.extend_from_slice(&nonce);
+    encrypted.extend_from_slice(&ciphertext);
+    Ok(encrypted)
+}
+
+/// Decrypt `contents` encrypted with [`encrypt`] using the same `password`.
+pub(crate) fn decrypt(password: &str, contents: &[u8]) -> Result<Vec<u8>, LoadError> {
+    if contents.len() < HEADER_LEN || !contents.starts_with(MAGIC) {
+        return Err(LoadError::NotEncrypted);
+    }
+
+    let (header, ciphertext) = contents.split_at(HEADER_LEN);
+    let version = header[MAGIC.len()];
+    if version != VERSION {
+        return Err(LoadError::UnsupportedEncryption(version));
+    }
+
+    let salt = &header[MAGIC.len() + 1..][..SALT_LEN];
+    let nonce = &header[MAGIC.len() + 1 + SALT_LEN..];
+
+    let key = derive_key(password, salt).map_err(|_| LoadError::Decrypt)?;
+    XChaCha20Poly1305::new(&key)
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| LoadError::Decrypt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let encrypted = encrypt("password", b"secret").unwrap();
+        assert!(encrypted.starts_with(MAGIC));
+        assert_eq!(decrypt("password", &encrypted).unwrap(), b"secret");
+    }
+
+    #[test]
+    fn wrong_password() {
+        let encrypted = encrypt("password", b"secret").unwrap();
+        assert!(matches!(
+            decrypt("wrong", &encrypted),
+            Err(LoadError::Decrypt)
+        ));
+    }
+
+    #[test]
+    fn tampered() {
+        let mut encrypted = encrypt("password", b"secret").unwrap();
+        *encrypted.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            decrypt("password", &encrypted),
+            Err(LoadError::Decrypt)
+        ));
+    }
+
+    #[test]
+    fn not_encrypted() {
+        assert!(matches!(
+            decrypt("password", b"secret"),
+            Err(LoadError::NotEncrypted)
+        ));
+
+        let mut encrypted = encrypt("password", b"secret").unwrap();
+        encrypted[MAGIC.len()] = VERSION + 1;
+        assert!(matches!(
+            decrypt("password", &encrypted),
+            Err(LoadError::UnsupportedEncryption(version)) if version == VERSION + 1
+        ));
+    }
+}
@@ -0,0 +1,193 @@
+//! Versioned envelope for file contents.
+//!
+//! Saved contents are prefixed with a header consisting of magic bytes and
+//! little-endian `u32` version, followed by the payload.
+
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use bevy_ecs::prelude::*;
+
+use crate::{FileDialogPlugin, LoadError};
+
+type Migration = Box<dyn Fn(Vec<u8>) -> Result<Vec<u8>, String> + Send + Sync + 'static>;
+
+/// Versioned file format with migrations from older versions. Register it for
+/// a marker with [`FileDialogPlugin::with_file_format`].
+///
+/// When file is saved, its contents are prefixed with magic bytes and the
+/// current version of the format. When file is loaded, the header is
+/// checked and removed and the payload is upgraded to the current version by
+/// running all migrations from the version the file was saved with.
+pub struct VersionedFormat {
+    magic: Vec<u8>,
+    version: u32,
+    migrations: BTreeMap<u32, Migration>,
+}
+
+impl VersionedFormat {
+    /// Create format identified by `magic` bytes, files will be saved with
+    /// `version`.
+    pub fn new(magic: impl Into<Vec<u8>>, version: u32) -> Self {
+        Self {
+            magic: magic.into(),
+            version,
+            migrations: BTreeMap::new(),
+        }
+    }
+
+    /// Add migration that upgrades payload saved with version `from` to
+    /// version `from + 1`. Returning error from the migration fails the load
+    /// with [`LoadError::Migration`].
+    pub fn with_migration(
+        mut self,
+        from: u32,
+        migrate: impl Fn(Vec<u8>) -> Result<Vec<u8>, String> + Send + Sync + 'static,
+    ) -> Self {
+        self.migrations.insert(from, Box::new(migrate));
+        self
+    }
+
+    /// Current version of the format, that is used when saving.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Prefix `payload` with header of current version.
+    pub(crate) fn add_header(&self, payload: Vec<u8>) -> Vec<u8> {
+        let mut contents = Vec::with_capacity(self.magic.len() + 4 + payload.len());
+        contents.extend_from_slice(&self.magic);
+        contents.extend_from_slice(&self.version.to_le_bytes());
+        contents.extend_from_slice(&payload);
+        contents
+    }
+
+    /// Strip header from `contents` and migrate the payload to current
+    /// version.
+    pub(crate) fn migrate(&self, contents: Vec<u8>) -> Result<Vec<u8>, LoadError> {
+        let header_len = self.magic.len() + 4;
+        if contents.len() < header_len || !contents.starts_with(&self.magic) {
+            return Err(LoadError::InvalidHeader);
+        }
+
+        let version_bytes = contents[self.magic.len()..header_len].try_into().unwrap();
+        let mut version = u32::from_le_bytes(version_bytes);
+        if version > self.version {
+            return Err(LoadError::UnsupportedVersion {
+                version,
+                current: self.version,
+            });
+        }
+
+        let mut payload = contents[header_len..].to_vec();
+        while version < self.version {
+            let Some(migrate) = self.migrations.get(&version) else {
+                return Err(LoadError::MissingMigration(version));
+            };
+
+            payload = migrate(payload).map_err(|message| LoadError::Migration {
+                from: version,
+                message,
+            })?;
+            version += 1;
+        }
+
+        Ok(payload)
+    }
+}
+
+#[derive(Resource)]
+pub(crate) struct FileFormat<T>(pub(crate) Arc<VersionedFormat>, PhantomData<T>);
+
+impl FileDialogPlugin {
+    /// Use versioned `format` for all files saved and loaded with marker `T`.
+    /// Requires [`FileDialogPlugin::with_save_file::<T>`] or
+    /// [`FileDialogPlugin::with_load_file::<T>`].
+    ///
+    /// Loaded files that don't match the format or were saved with newer
+    /// version send [`DialogFileLoadFailed<T>`](crate::DialogFileLoadFailed)
+    /// instead of [`DialogFileLoaded<T>`](crate::DialogFileLoaded).
+    pub fn with_file_format<T: Send + Sync + 'static>(mut self, format: VersionedFormat) -> Self {
+        let format = Arc::new(format);
+        self.0.push(Box::new(move |app| {
+            app.insert_resource(FileFormat::<T>(format.clone(), PhantomData));
+        }));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format() -> VersionedFormat {
+        VersionedFormat::new(*b"LVL", 2)
+            .with_migration(0, |mut payload| {
+                payload.extend_from_slice(b"+1");
+                Ok(payload)
+            })
+            .with_migration(1, |mut payload| {
+                payload.extend_from_slice(b"+2");
+                Ok(payload)
+            })
+    }
+
+    #[test]
+    fn header() {
+        let contents = format().add_header(b"level".to_vec());
+        assert_eq!(contents, b"LVL\x02\x00\x00\x00level");
+        assert_eq!(format().migrate(contents).unwrap(), b"level");
+    }
+
+    #[test]
+    fn migrations() {
+        let old = VersionedFormat::new(*b"LVL", 0).add_header(b"level".to_vec());
+        assert_eq!(format().migrate(old).unwrap(), b"level+1+2");
+
+        let old = VersionedFormat::new(*b"LVL", 1).add_header(b"level".to_vec());
+        assert_eq!(format().migrate(old).unwrap(), b"level+2");
+    }
+
+    #[test]
+    fn invalid_header() {
+        assert!(matches!(
+            format().migrate(b"level".to_vec()),
+            Err(LoadError::InvalidHeader)
+        ));
+        assert!(matches!(
+            format().migrate(b"LVL\x02".to_vec()),
+            Err(LoadError::InvalidHeader)
+        ));
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let newer = VersionedFormat::new(*b"LVL", 3).add_header(b"level".to_vec());
+        assert!(matches!(
+            format().migrate(newer),
+            Err(LoadError::UnsupportedVersion {
+                version: 3,
+                current: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn failed_migration() {
+        let format =
+            VersionedFormat::new(*b"LVL", 2).with_migration(1, |_| Err("broken".to_string()));
+
+        let old = VersionedFormat::new(*b"LVL", 0).add_header(b"level".to_vec());
+        assert!(matches!(
+            format.migrate(old),
+            Err(LoadError::MissingMigration(0))
+        ));
+
+        let old = VersionedFormat::new(*b"LVL", 1).add_header(b"level".to_vec());
+        assert!(matches!(
+            format.migrate(old),
+            Err(LoadError::Migration { from: 1, message }) if message == "broken"
+        ));
+    }
+}
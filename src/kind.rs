@@ -0,0 +1,394 @@
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+use bevy_ecs::prelude::*;
+use rfd::{AsyncFileDialog, FileHandle};
+
+#[cfg(feature = "asset")]
+use crate::asset::AssetFilters;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::mock::MockDialogBackend;
+use crate::FileDialogPlugin;
+
+/// Default configuration of dialogs opened with marker type implementing
+/// this trait. Register it with [`FileDialogPlugin::with_dialog_kind`].
+///
+/// The defaults are applied automatically by all functions of
+/// [`FileDialog`](crate::FileDialog) that open dialog with the marker, each
+/// default is used only when it was not set on the dialog itself, so the
+/// defaults can be overridden per call:
+///
+/// ```rust
+/// struct LevelFile;
+///
+/// impl DialogKind for LevelFile {
+///     fn title() -> Option<String> {
+///         Some("Open Level".to_string())
+///     }
+///
+///     fn filters() -> Vec<(String, Vec<String>)> {
+///         vec![("Level".to_string(), vec!["ron".to_string()])]
+///     }
+/// }
+///
+/// fn system(mut commands: Commands) {
+///     commands.dialog().load_file::<LevelFile>();
+/// }
+/// ```
+///
+/// The trait can also be derived with [`FileDialogKind`](crate::FileDialogKind):
+///
+/// ```rust
+/// #[derive(FileDialogKind)]
+/// #[dialog(title = "Open Level", filter("Level", "ron", "lvl"), save, load)]
+/// struct LevelFile;
+///
+/// App::new().add_plugins(FileDialogPlugin::new().with_dialog_kind::<LevelFile>());
+/// ```
+pub trait DialogKind: Send + Sync + 'static {
+    /// Title of the dialog, used unless [`FileDialog::set_title`] is called.
+    ///
+    /// [`FileDialog::set_title`]: crate::FileDialog::set_title
+    fn title() -> Option<String> {
+        None
+    }
+
+    /// Named extension filters of the dialog, used unless any filter is added
+    /// with [`FileDialog::add_filter`] or similar function.
+    ///
+    /// [`FileDialog::add_filter`]: crate::FileDialog::add_filter
+    fn filters() -> Vec<(String, Vec<String>)> {
+        Vec::new()
+    }
+
+    /// Starting file name of the dialog, used unless
+    /// [`FileDialog::set_file_name`] is called.
+    ///
+    /// [`FileDialog::set_file_name`]: crate::FileDialog::set_file_name
+    fn file_name() -> Option<String> {
+        None
+    }
+
+    /// Starting directory of the dialog, used unless
+    /// [`FileDialog::set_directory`] is called.
+    ///
+    /// [`FileDialog::set_directory`]: crate::FileDialog::set_directory
+    fn directory() -> Option<PathBuf> {
+        None
+    }
+
+    /// Register the marker with `plugin`, called from
+    /// [`FileDialogPlugin::with_dialog_kind`]. Use it to call
+    /// [`FileDialogPlugin::with_save_file`],
+    /// [`FileDialogPlugin::with_load_file`] and other `with_*` functions for
+    /// the marker in one place.
+    fn register(plugin: FileDialogPlugin) -> FileDialogPlugin {
+        plugin
+    }
+}
+
+#[derive(Resource)]
+struct DialogDefaults<T> {
+    title: Option<String>,
+    filters: Vec<(String, Vec<String>)>,
+    file_name: Option<String>,
+    directory: Option<PathBuf>,
+    marker: PhantomData<T>,
+}
+
+impl FileDialogPlugin {
+    /// Use defaults of [`DialogKind`] for all dialogs opened with marker `T`
+    /// and register the marker with [`DialogKind::register`]. Unless the
+    /// registration is implemented, requires any of the other `with_*`
+    /// functions for the same marker.
+    pub fn with_dialog_kind<T: DialogKind>(self) -> Self {
+        let mut plugin = T::register(self);
+        plugin.0.push(Box::new(|app| {
+            app.insert_resource(DialogDefaults::<T> {
+                title: T::title(),
+                filters: T::filters(),
+                file_name: T::file_name(),
+                directory: T::directory(),
+                marker: PhantomData,
+            });
+        }));
+        plugin
+    }
+}
+
+/// Configuration of [`FileDialog`](crate::FileDialog) that is applied when the
+/// dialog gets opened, because it depends on the marker or on resources.
+#[derive(Default)]
+pub(crate) struct DialogConfig {
+    #[cfg(feature = "asset")]
+    pub(crate) asset_filters: AssetFilters,
+    pub(crate) has_title: bool,
+    pub(crate) has_filters: bool,
+    pub(crate) has_file_name: bool,
+    pub(crate) has_directory: bool,
+}
+
+impl DialogConfig {
+    /// Add asset filters to `dialog` and apply defaults of [`DialogKind`]
+    /// registered for marker `T` that were not overridden.
+    pub(crate) fn apply<T: Send + Sync + 'static>(
+        &self,
+        mut dialog: AsyncFileDialog,
+        world: &World,
+    ) -> Dialog {
+        dialog = self.add_asset_filters(dialog, world);
+
+        let Some(defaults) = world.get_resource::<DialogDefaults<T>>() else {
+            return Dialog::new(dialog, world);
+        };
+
+        if let Some(title) = defaults.title.as_ref().filter(|_| !self.has_title) {
+            dialog = dialog.set_title(title);
+        }
+
+        if !self.has_filters {
+            for (name, extensions) in &defaults.filters {
+                dialog = dialog.add_filter(name, extensions);
+            }
+        }
+
+        if let Some(file_name) = defaults.file_name.as_ref().filter(|_| !self.has_file_name) {
+            dialog = dialog.set_file_name(file_name);
+        }
+
+        if let Some(directory) = defaults.directory.as_ref().filter(|_| !self.has_directory) {
+            dialog = dialog.set_directory(directory);
+        }
+
+        Dialog::new(dialog, world)
+    }
+
+    /// Add asset filters to `dialog`, without defaults of any marker.
+    pub(crate) fn apply_asset_filters(&self, dialog: AsyncFileDialog, world: &World) -> Dialog {
+        Dialog::new(self.add_asset_filters(dialog, world), world)
+    }
+
+    #[cfg_attr(not(feature = "asset"), allow(unused_variables))]
+    fn add_asset_filters(&self, dialog: AsyncFileDialog, world: &World) -> AsyncFileDialog {
+        #[cfg(feature = "asset")]
+        return self.asset_filters.apply(dialog, world);
+
+        #[cfg(not(feature = "asset"))]
+        dialog
+    }
+
+    /// All extensions of filters that [`DialogConfig::apply`] adds together
+    /// with `extensions` added with [`FileDialog::add_filter`](crate::FileDialog::add_filter).
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn extensions<T: Send + Sync + 'static>(
+        &self,
+        extensions: Vec<String>,
+        world: &World,
+    ) -> Vec<String> {
+        let defaults = world
+            .get_resource::<DialogDefaults<T>>()
+            .filter(|_| !self.has_filters)
+            .map(|defaults| defaults.filters.clone())
+            .unwrap_or_default();
+
+        #[cfg(feature = "asset")]
+        let asset_filters = self.asset_filters.resolve(world);
+        #[cfg(not(feature = "asset"))]
+        let asset_filters = Vec::new();
+
+        asset_filters
+            .into_iter()
+            .chain(defaults)
+            .flat_map(|(_, extensions)| extensions)
+            .chain(extensions)
+            .collect()
+    }
+}
+
+/// Dialog with applied [`DialogConfig`] that is ready to be opened, answered
+/// by [`MockDialogBackend`] instead of native dialog when the resource exists.
+pub(crate) enum Dialog {
+    Native(AsyncFileDialog),
+    #[cfg(not(target_arch = "wasm32"))]
+    Mock(Vec<PathBuf>),
+}
+
+impl Dialog {
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+    fn new(dialog: AsyncFileDialog, world: &World) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(backend) = world.get_resource::<MockDialogBackend>() {
+            return Self::Mock(backend.next());
+        }
+
+        Self::Native(dialog)
+    }
+
+    pub(crate) async fn pick_file(self) -> Option<FileHandle> {
+        match self {
+            Self::Native(dialog) => dialog.pick_file().await,
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Mock(paths) => paths.into_iter().next().map(FileHandle::from),
+        }
+    }
+
+    pub(crate) async fn save_file(self) -> Option<FileHandle> {
+        match self {
+            Self::Native(dialog) => dialog.save_file().await,
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Mock(paths) => paths.into_iter().next().map(FileHandle::from),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) async fn pick_folder(self) -> Option<FileHandle> {
+        match self {
+            Self::Native(dialog) => dialog.pick_folder().await,
+            Self::Mock(paths) => paths.into_iter().next().map(FileHandle::from),
+        }
+    }
+
+    pub(crate) async fn pick_files(self) -> Option<Vec<FileHandle>> {
+        match self {
+            Self::Native(dialog) => dialog.pick_files().await,
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Mock(paths) if paths.is_empty() => None,
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Mock(paths) => Some(paths.into_iter().map(FileHandle::from).collect()),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) async fn pick_folders(self) -> Option<Vec<FileHandle>> {
+        match self {
+            Self::Native(dialog) => dialog.pick_folders().await,
+            Self::Mock(paths) if paths.is_empty() => None,
+            Self::Mock(paths) => Some(paths.into_iter().map(FileHandle::from).collect()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::prelude::*;
+    use bevy_ecs::world::CommandQueue;
+
+    use super::*;
+    use crate::{FileDialog, FileDialogExt};
+
+    struct LevelFile;
+
+    impl DialogKind for LevelFile {
+        fn title() -> Option<String> {
+            Some("Open Level".to_string())
+        }
+
+        fn filters() -> Vec<(String, Vec<String>)> {
+            vec![("Level".to_string(), vec!["lvl".to_string()])]
+        }
+
+        fn file_name() -> Option<String> {
+            Some("level.lvl".to_string())
+        }
+
+        fn directory() -> Option<PathBuf> {
+            Some(PathBuf::from("levels"))
+        }
+    }
+
+    /// Debug output of the dialog opened with marker `T` after applying its
+    /// defaults to dialog configured with `configure`.
+    fn applied<T: Send + Sync + 'static>(
+        app: &mut App,
+        configure: impl for<'w, 's, 'a> FnOnce(FileDialog<'w, 's, 'a>) -> FileDialog<'w, 's, 'a>,
+    ) -> String {
+        let world = app.world_mut();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, world);
+        let dialog = configure(commands.dialog());
+
+        match dialog.config.apply::<T>(dialog.dialog.clone(), world) {
+            Dialog::Native(dialog) => format!("{dialog:?}"),
+            #[cfg(not(target_arch = "wasm32"))]
+            Dialog::Mock(_) => unreachable!("no mock backend"),
+        }
+    }
+
+    #[derive(crate::FileDialogKind)]
+    #[dialog(title = "Open Map", file_name = "map.ron", directory = "maps")]
+    #[dialog(filter("Map", "map", "ron"), save)]
+    struct MapFile;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(FileDialogPlugin::new().with_dialog_kind::<LevelFile>());
+        app
+    }
+
+    #[test]
+    fn defaults_applied() {
+        let applied = applied::<LevelFile>(&mut app(), |dialog| dialog);
+
+        assert!(applied.contains("\"Open Level\""));
+        assert!(applied.contains("\"Level\""));
+        assert!(applied.contains("\"lvl\""));
+        assert!(applied.contains("\"level.lvl\""));
+        assert!(applied.contains("\"levels\""));
+    }
+
+    #[test]
+    fn defaults_only_for_registered_marker() {
+        let applied = applied::<()>(&mut app(), |dialog| dialog);
+
+        assert!(!applied.contains("Level"));
+        assert!(!applied.contains("level"));
+    }
+
+    #[test]
+    fn explicit_calls_override_defaults() {
+        let applied = applied::<LevelFile>(&mut app(), |dialog| {
+            dialog
+                .set_title("Import")
+                .add_filter("Map", &["map"])
+                .set_file_name("custom.map")
+                .set_directory("maps")
+        });
+
+        assert!(applied.contains("\"Import\""));
+        assert!(applied.contains("\"map\""));
+        assert!(applied.contains("\"custom.map\""));
+        assert!(applied.contains("\"maps\""));
+        assert!(!applied.contains("Level"));
+        assert!(!applied.contains("lvl"));
+    }
+
+    #[test]
+    fn each_default_overridden_separately() {
+        let applied = applied::<LevelFile>(&mut app(), |dialog| dialog.set_title("Import"));
+
+        assert!(applied.contains("\"Import\""));
+        assert!(!applied.contains("Open Level"));
+        assert!(applied.contains("\"lvl\""));
+        assert!(applied.contains("\"level.lvl\""));
+        assert!(applied.contains("\"levels\""));
+    }
+
+    #[test]
+    fn derived_defaults_applied() {
+        let mut app = App::new();
+        app.add_plugins(FileDialogPlugin::new().with_dialog_kind::<MapFile>());
+
+        let applied = applied::<MapFile>(&mut app, |dialog| dialog);
+        assert!(applied.contains("\"Open Map\""));
+        assert!(applied.contains("\"Map\""));
+        assert!(applied.contains("\"map\""));
+        assert!(applied.contains("\"ron\""));
+        assert!(applied.contains("\"map.ron\""));
+        assert!(applied.contains("\"maps\""));
+
+        // `save` registered the marker with `FileDialogPlugin::with_save_file`
+        assert!(app
+            .world()
+            .contains_resource::<Messages<crate::DialogFileSaved<MapFile>>>());
+    }
+}
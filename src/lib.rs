@@ -3,18 +3,29 @@
 //! Bevy plugin that allows you to save and load files with file dialogs.
 //!
 //! In order to use it you need to add [`FileDialogPlugin`] to your [`App`] with
-//! at least one or more calls to:
+//! one or more calls to:
 //! - [`FileDialogPlugin::with_save_file::<T>`]
 //! - [`FileDialogPlugin::with_load_file::<T>`]
 //! - [`FileDialogPlugin::with_pick_directory::<T>`]
 //! - [`FileDialogPlugin::with_pick_file::<T>`]
 //!
+//! or register the same from any plugin with [`FileDialogAppExt`], for example
+//! [`FileDialogAppExt::register_file_dialog_load::<T>`].
+//!
 //! these functions can be called as many times as you want, the type parameter
 //! acts as marker that allows you to call:
 //! - [`FileDialog::save_file`]
+//! - [`FileDialog::save_file_with`]
+//! - [`FileDialog::save_file_from_world`]
+//! - [`FileDialog::save_text`]
+//! - [`FileDialog::save_file_with_writer`]
 //!   - for [`FileDialogPlugin::with_save_file::<T>`]
 //! - [`FileDialog::load_file`]
 //! - [`FileDialog::load_multiple_files`]
+//! - [`FileDialog::load_text`]
+//! - [`FileDialog::load_directory`]
+//! - [`FileDialog::load_file_streaming`]
+//! - [`FileDialog::load_file_shared`]
 //!   - for [`FileDialogPlugin::with_load_file::<T>`]
 //! - [`FileDialog::pick_directory_path`]
 //! - [`FileDialog::pick_multiple_directory_paths`]
@@ -62,32 +73,114 @@
 use std::io;
 use std::marker::PhantomData;
 use std::path::Path;
+use std::sync::Arc;
 
 use bevy_app::prelude::*;
 use bevy_derive::Deref;
 use bevy_ecs::prelude::*;
 use bevy_tasks::prelude::*;
 use bevy_winit::{EventLoopProxy, EventLoopProxyWrapper, WinitUserEvent};
-use crossbeam_channel::{bounded, Receiver, Sender};
-use rfd::AsyncFileDialog;
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
+use rfd::{AsyncFileDialog, FileHandle};
+
+use crate::codec::Codec;
+use crate::kind::{Dialog, DialogConfig};
+use crate::outcome::IntoOutcome;
+use crate::parallel::load_concurrently;
+use crate::sniff::sniff;
+
+// Lets tests derive `FileDialogKind`, which refers to the crate by its name.
+#[cfg(test)]
+extern crate self as bevy_file_dialog;
 
+mod app;
+#[cfg(feature = "asset")]
+mod asset;
+mod batch;
+mod callback;
+#[cfg(feature = "checksum")]
+mod checksum;
+mod codec;
+mod deferred;
+#[cfg(not(target_arch = "wasm32"))]
+mod directory;
+mod dynamic;
+#[cfg(feature = "encryption")]
+mod encryption;
+mod format;
+mod kind;
+#[cfg(not(target_arch = "wasm32"))]
+mod metadata;
+#[cfg(not(target_arch = "wasm32"))]
+mod mock;
+mod outcome;
+mod parallel;
 #[cfg(not(target_arch = "wasm32"))]
 mod pick;
+#[cfg(all(feature = "remote", not(target_arch = "wasm32")))]
+mod remote;
+mod request;
+#[cfg(not(target_arch = "wasm32"))]
+mod shared;
+mod sniff;
+#[cfg(not(target_arch = "wasm32"))]
+mod stream;
+mod task;
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod testing;
+mod text;
+#[cfg(not(target_arch = "wasm32"))]
+mod writer;
 
+pub use app::FileDialogAppExt;
+#[cfg(feature = "asset")]
+pub use asset::FilterPreset;
+pub use batch::*;
+pub use bevy_file_dialog_derive::FileDialogKind;
+pub use callback::DialogCallback;
+#[cfg(feature = "checksum")]
+pub use checksum::Checksum;
+pub use codec::{Compression, LoadError};
+#[cfg(not(target_arch = "wasm32"))]
+pub use directory::*;
+pub use dynamic::*;
+pub use format::VersionedFormat;
+pub use kind::DialogKind;
+#[cfg(not(target_arch = "wasm32"))]
+pub use metadata::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use mock::MockDialogBackend;
+pub use outcome::DialogOutcome;
 #[cfg(not(target_arch = "wasm32"))]
 pub use pick::*;
+#[cfg(all(feature = "remote", not(target_arch = "wasm32")))]
+pub use remote::*;
+pub use request::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use shared::*;
+pub use sniff::ContentType;
+#[cfg(not(target_arch = "wasm32"))]
+pub use stream::*;
+pub use task::AsyncDialog;
+pub use text::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use writer::*;
 
 pub mod prelude {
     //! Prelude containing all types you need for saving/loading files with dialogs.
     pub use crate::{
-        DialogFileLoadCanceled, DialogFileLoaded, DialogFileSaveCanceled, DialogFileSaved,
-        FileDialogExt, FileDialogPlugin,
+        AsyncDialog, DialogFileLoadCanceled, DialogFileLoadFailed, DialogFileLoaded,
+        DialogFileSaveCanceled, DialogFileSaved, DialogFileTooLarge, DialogFilesLoaded, DialogKind,
+        DialogKindId, DialogOutcome, DialogTextLoaded, DynamicDialogOutcome, DynamicDialogResult,
+        FileDialogAppExt, FileDialogExt, FileDialogKind, FileDialogPlugin, FileDialogRequest,
+        FileDialogRequestKind, FileDialogResponse, LineEnding, TextEncoding, TextFormat,
     };
 
     #[cfg(not(target_arch = "wasm32"))]
     pub use crate::{
-        DialogDirectoryPickCanceled, DialogDirectoryPicked, DialogFilePickCanceled,
-        DialogFilePicked,
+        DialogDirectoryLoaded, DialogDirectoryPickCanceled, DialogDirectoryPicked,
+        DialogFileLoadProgress, DialogFileLoadedShared, DialogFilePickCanceled, DialogFilePicked,
+        DialogFileSaveProgress, DialogPathsPicked, FileBuffer, FileLoadHandle, FileMetadata,
     };
 }
 
@@ -109,9 +202,10 @@ impl<T> SaveContents for T where T: Send + Sync + 'static {}
 impl<T> LoadContents for T where T: Send + Sync + 'static {}
 
 impl FileDialogPlugin {
-    /// Create new file dialog plugin. Do not forget to call at least one
-    /// `with_save_file`, `with_load_file` or `with_pick_directory` on the plugin to allow you to
-    /// save/load files and pick directories.
+    /// Create new file dialog plugin. Call `with_save_file`, `with_load_file`
+    /// or `with_pick_directory` on the plugin to allow you to save/load files
+    /// and pick directories, or register them from any plugin with
+    /// [`FileDialogAppExt`].
     pub fn new() -> Self {
         Default::default()
     }
@@ -121,17 +215,7 @@ impl FileDialogPlugin {
     /// will receive [`DialogFileSaved<T>`] in your systems when `save_file`
     /// completes.
     pub fn with_save_file<T: SaveContents>(mut self) -> Self {
-        self.0.push(Box::new(|app| {
-            let (tx, rx) = bounded::<DialogResult<DialogFileSaved<T>>>(1);
-            app.insert_resource(StreamSender(tx));
-            app.insert_resource(StreamReceiver(rx));
-            app.add_message::<DialogFileSaved<T>>();
-            app.add_message::<DialogFileSaveCanceled<T>>();
-            app.add_systems(
-                First,
-                handle_dialog_result::<DialogFileSaved<T>, DialogFileSaveCanceled<T>>,
-            );
-        }));
+        self.0.push(Box::new(register_save_file::<T>));
         self
     }
 
@@ -140,21 +224,122 @@ impl FileDialogPlugin {
     /// will receive [`DialogFileLoaded<T>`] in your systems when `load_file`
     /// completes.
     pub fn with_load_file<T: LoadContents>(mut self) -> Self {
-        self.0.push(Box::new(|app| {
-            let (tx, rx) = bounded::<DialogResult<DialogFileLoaded<T>>>(1);
-            app.insert_resource(StreamSender(tx));
-            app.insert_resource(StreamReceiver(rx));
-            app.add_message::<DialogFileLoaded<T>>();
-            app.add_message::<DialogFileLoadCanceled<T>>();
-            app.add_systems(
-                First,
-                handle_dialog_result::<DialogFileLoaded<T>, DialogFileLoadCanceled<T>>,
-            );
+        self.0.push(Box::new(register_load_file::<T>));
+        self
+    }
+
+    /// Limit size of files that can be loaded with marker `T`. When user picks
+    /// file larger than `max_size` bytes, its contents are not read and
+    /// [`DialogFileTooLarge<T>`] gets sent instead of [`DialogFileLoaded<T>`].
+    ///
+    /// The limit can be overridden for single dialog with
+    /// [`FileDialog::set_max_file_size`]. Requires
+    /// [`FileDialogPlugin::with_load_file::<T>`].
+    pub fn with_max_file_size<T: LoadContents>(mut self, max_size: u64) -> Self {
+        self.0.push(Box::new(move |app| {
+            app.insert_resource(MaxFileSize::<T>(max_size, PhantomData));
         }));
         self
     }
+
+    /// Send [`DialogOutcome<T>`] instead of separate events for dialogs
+    /// opened with marker `T`, so that single system can handle every
+    /// outcome with `match`. Requires any of the other `with_*` functions for
+    /// the same marker.
+    ///
+    /// Replaces [`DialogFileLoaded<T>`], [`DialogFileSaved<T>`],
+    /// `DialogFilePicked<T>`, `DialogDirectoryPicked<T>`,
+    /// [`DialogFileTooLarge<T>`], [`DialogFileLoadFailed<T>`] and the
+    /// canceled events of load, save and pick dialogs. Events specific to
+    /// some functions, like [`DialogTextLoaded<T>`] or progress events, are
+    /// still sent separately together with their canceled events.
+    pub fn with_outcome_message<T: Send + Sync + 'static>(mut self) -> Self {
+        self.0.push(Box::new(register_outcome_message::<T>));
+        self
+    }
+}
+
+/// Register [`DialogOutcome<T>`] event, which makes the handlers send it
+/// instead of separate events.
+fn register_outcome_message<T: Send + Sync + 'static>(app: &mut App) {
+    app.add_message::<DialogOutcome<T>>();
+}
+
+/// Register everything needed for [`FileDialog::save_file`] and other save
+/// functions with marker `T`, does nothing if it is already registered.
+fn register_save_file<T: SaveContents>(app: &mut App) {
+    if app
+        .world()
+        .contains_resource::<StreamSender<DialogResult<DialogFileSaved<T>>>>()
+    {
+        return;
+    }
+
+    let (tx, rx) = bounded::<DialogResult<DialogFileSaved<T>>>(1);
+    app.insert_resource(StreamSender(tx));
+    app.insert_resource(StreamReceiver(rx));
+    app.add_message::<DialogFileSaved<T>>();
+    app.add_message::<DialogFileSaveCanceled<T>>();
+    app.add_systems(
+        First,
+        handle_dialog_outcome::<DialogFileSaved<T>, DialogFileSaveCanceled<T>>,
+    );
+
+    deferred::register_deferred_save::<T>(app);
+    #[cfg(not(target_arch = "wasm32"))]
+    writer::register_save_progress::<T>(app);
+}
+
+/// Register everything needed for [`FileDialog::load_file`] and other load
+/// functions with marker `T`, does nothing if it is already registered.
+fn register_load_file<T: LoadContents>(app: &mut App) {
+    if app
+        .world()
+        .contains_resource::<StreamSender<DialogResult<DialogFileLoaded<T>>>>()
+    {
+        return;
+    }
+
+    // Files loaded with `FileDialog::set_send_as_loaded` are sent one by one,
+    // the task must not block until they are consumed
+    let (tx, rx) = unbounded::<DialogResult<DialogFileLoaded<T>>>();
+    app.insert_resource(StreamSender(tx));
+    app.insert_resource(StreamReceiver(rx));
+    app.add_message::<DialogFileLoaded<T>>();
+    app.add_message::<DialogFileLoadCanceled<T>>();
+    app.add_systems(
+        First,
+        handle_dialog_outcome::<DialogFileLoaded<T>, DialogFileLoadCanceled<T>>,
+    );
+
+    #[cfg(not(target_arch = "wasm32"))]
+    directory::register_load_directory::<T>(app);
+    #[cfg(not(target_arch = "wasm32"))]
+    stream::register_load_progress::<T>(app);
+    #[cfg(not(target_arch = "wasm32"))]
+    shared::register_load_shared::<T>(app);
+
+    // Single dialog may send many of these, for example when loading
+    // multiple files, the task must not block until they are consumed
+    let (tx, rx) = unbounded::<DialogFileTooLarge<T>>();
+    app.insert_resource(StreamSender(tx));
+    app.insert_resource(StreamReceiver(rx));
+    app.add_message::<DialogFileTooLarge<T>>();
+    app.add_systems(First, handle_outcome_message::<DialogFileTooLarge<T>>);
+
+    // Same as for too large files above
+    let (tx, rx) = unbounded::<DialogFileLoadFailed<T>>();
+    app.insert_resource(StreamSender(tx));
+    app.insert_resource(StreamReceiver(rx));
+    app.add_message::<DialogFileLoadFailed<T>>();
+    app.add_systems(First, handle_outcome_message::<DialogFileLoadFailed<T>>);
+
+    text::register_load_text::<T>(app);
 }
 
+#[derive(Resource)]
+struct MaxFileSize<T: LoadContents>(u64, PhantomData<T>);
+
 #[derive(Resource, Deref)]
 struct StreamReceiver<T>(Receiver<T>);
 
@@ -187,6 +372,51 @@ fn handle_dialog_result<E: Message, C: Message + Default>(
     }
 }
 
+fn handle_message<E: Message>(receiver: Res<StreamReceiver<E>>, mut ev: MessageWriter<E>) {
+    ev.write_batch(receiver.try_iter());
+}
+
+/// Same as [`handle_dialog_result`], but sends [`DialogOutcome`] instead when
+/// it was enabled with [`FileDialogPlugin::with_outcome_message`].
+fn handle_dialog_outcome<E: IntoOutcome, C: Message + Default>(
+    receiver: Res<StreamReceiver<DialogResult<E>>>,
+    ev_done: MessageWriter<E>,
+    ev_canceled: MessageWriter<C>,
+    ev_outcome: Option<MessageWriter<DialogOutcome<E::Marker>>>,
+) {
+    let Some(mut ev_outcome) = ev_outcome else {
+        return handle_dialog_result(receiver, ev_done, ev_canceled);
+    };
+
+    for result in receiver.try_iter() {
+        match result {
+            DialogResult::Single(event) => {
+                ev_outcome.write(event.into_outcome());
+            }
+            DialogResult::Batch(events) => {
+                ev_outcome.write_batch(events.into_iter().map(IntoOutcome::into_outcome));
+            }
+            DialogResult::Canceled => {
+                ev_outcome.write(DialogOutcome::Canceled);
+            }
+        }
+    }
+}
+
+/// Same as [`handle_message`], but sends [`DialogOutcome`] instead when it
+/// was enabled with [`FileDialogPlugin::with_outcome_message`].
+fn handle_outcome_message<E: IntoOutcome>(
+    receiver: Res<StreamReceiver<E>>,
+    ev: MessageWriter<E>,
+    ev_outcome: Option<MessageWriter<DialogOutcome<E::Marker>>>,
+) {
+    let Some(mut ev_outcome) = ev_outcome else {
+        return handle_message(receiver, ev);
+    };
+
+    ev_outcome.write_batch(receiver.try_iter().map(IntoOutcome::into_outcome));
+}
+
 /// Event that gets sent when file contents get saved to file system.
 #[derive(Message)]
 pub struct DialogFileSaved<T: SaveContents> {
@@ -211,8 +441,23 @@ pub struct DialogFileLoaded<T: LoadContents> {
     /// Name of loaded file.
     pub file_name: String,
 
-    /// Byte contents of loaded file.
-    pub contents: Vec<u8>,
+    /// Byte contents of loaded file, shared with [`DialogFilesLoaded<T>`]
+    /// when [`FileDialogPlugin::with_batch_messages::<T>`] is used. Use
+    /// [`Arc::unwrap_or_clone`] to take them, which does not copy them
+    /// unless they are shared.
+    pub contents: Arc<Vec<u8>>,
+
+    /// Type of the file detected from its first bytes, present only when
+    /// [`FileDialog::set_verify_content`] is enabled and the type is
+    /// recognized.
+    pub content_type: Option<ContentType>,
+
+    /// Metadata of loaded file, present only when
+    /// [`FileDialog::set_collect_metadata`] is enabled.
+    ///
+    /// Does not exist in wasm, you can use this on native platforms only.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub metadata: Option<FileMetadata>,
 
     /// Path to loaded file.
     ///
@@ -223,6 +468,236 @@ pub struct DialogFileLoaded<T: LoadContents> {
     marker: PhantomData<T>,
 }
 
+impl<T: LoadContents> Clone for DialogFileLoaded<T> {
+    fn clone(&self) -> Self {
+        Self {
+            file_name: self.file_name.clone(),
+            contents: self.contents.clone(),
+            content_type: self.content_type,
+            #[cfg(not(target_arch = "wasm32"))]
+            metadata: self.metadata.clone(),
+            #[cfg(not(target_arch = "wasm32"))]
+            path: self.path.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Event that gets sent instead of [`DialogFileLoaded<T>`] when picked file is
+/// larger than the limit set with [`FileDialogPlugin::with_max_file_size`] or
+/// [`FileDialog::set_max_file_size`].
+#[derive(Message)]
+pub struct DialogFileTooLarge<T: LoadContents> {
+    /// Name of picked file.
+    pub file_name: String,
+
+    /// Actual size of picked file in bytes. For compressed files that were
+    /// too large after decompression, it is the number of bytes decompressed
+    /// before the limit was exceeded.
+    pub size: u64,
+
+    /// Maximum allowed size in bytes.
+    pub max_size: u64,
+
+    /// Path to picked file.
+    ///
+    /// Does not exist in wasm, you can use this on native platforms only.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub path: std::path::PathBuf,
+
+    marker: PhantomData<T>,
+}
+
+impl<T: LoadContents> DialogFileTooLarge<T> {
+    fn new(file: &FileHandle, size: u64, max_size: u64) -> Self {
+        Self {
+            file_name: file.file_name(),
+            size,
+            max_size,
+            #[cfg(not(target_arch = "wasm32"))]
+            path: file.path().to_path_buf(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns [`DialogFileTooLarge`] when `size` exceeds `max_size`.
+    fn check(file: &FileHandle, size: u64, max_size: Option<u64>) -> Result<(), Self> {
+        match max_size {
+            Some(max_size) if size > max_size => Err(Self::new(file, size, max_size)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Read contents of `file` unless it is larger than `max_size`.
+///
+/// On native platforms the size is checked before reading, in wasm the size
+/// is only known after the contents are read.
+async fn read_limited<T: LoadContents>(
+    file: &FileHandle,
+    max_size: Option<u64>,
+) -> Result<Vec<u8>, DialogFileTooLarge<T>> {
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Ok(metadata) = std::fs::metadata(file.path()) {
+        DialogFileTooLarge::check(file, metadata.len(), max_size)?;
+    }
+
+    let contents = file.read().await;
+    DialogFileTooLarge::check(file, contents.len() as u64, max_size)?;
+    Ok(contents)
+}
+
+/// Event that gets sent instead of [`DialogFileLoaded<T>`] when picked file
+/// was read, but its contents could not be decoded.
+#[derive(Message)]
+pub struct DialogFileLoadFailed<T: LoadContents> {
+    /// Name of picked file.
+    pub file_name: String,
+
+    /// Reason why the file could not be loaded.
+    pub error: LoadError,
+
+    /// Path to picked file.
+    ///
+    /// Does not exist in wasm, you can use this on native platforms only.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub path: std::path::PathBuf,
+
+    marker: PhantomData<T>,
+}
+
+impl<T: LoadContents> DialogFileLoadFailed<T> {
+    fn new(file: &FileHandle, error: LoadError) -> Self {
+        Self {
+            file_name: file.file_name(),
+            error,
+            #[cfg(not(target_arch = "wasm32"))]
+            path: file.path().to_path_buf(),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Encode `contents` with `codec` and write them to `file`.
+async fn write_encoded(file: &FileHandle, codec: &Codec, contents: Vec<u8>) -> io::Result<()> {
+    let contents = codec.encode(file, contents)?;
+    file.write(&contents).await?;
+
+    #[cfg(feature = "checksum")]
+    codec.checksum.write_sidecar(file, &contents)?;
+
+    Ok(())
+}
+
+/// Open save file `dialog` and save the `contents` to that file, shared by
+/// [`FileDialog::save_file`], [`FileDialog::save_file_async`] and
+/// [`AsyncDialog::save_file`].
+async fn save_file_outcome<T: SaveContents>(
+    dialog: Dialog,
+    codec: &Codec,
+    contents: Vec<u8>,
+) -> DialogOutcome<T> {
+    let Some(file) = dialog.save_file().await else {
+        return DialogOutcome::Canceled;
+    };
+
+    DialogOutcome::Saved(DialogFileSaved {
+        file_name: file.file_name(),
+        result: write_encoded(&file, codec, contents).await,
+        #[cfg(not(target_arch = "wasm32"))]
+        path: file.path().to_path_buf(),
+        marker: PhantomData,
+    })
+}
+
+/// Open pick file `dialog`, read its contents and decode them with `codec`,
+/// shared by [`FileDialog::load_file`], [`FileDialog::load_file_async`] and
+/// [`AsyncDialog::load_file`].
+async fn load_file_outcome<T: LoadContents>(
+    dialog: Dialog,
+    max_size: Option<u64>,
+    verify_content: bool,
+    codec: &Codec,
+    #[cfg(not(target_arch = "wasm32"))] collect_metadata: bool,
+) -> DialogOutcome<T> {
+    let Some(file) = dialog.pick_file().await else {
+        return DialogOutcome::Canceled;
+    };
+
+    load_picked_file(
+        &file,
+        max_size,
+        verify_content,
+        codec,
+        #[cfg(not(target_arch = "wasm32"))]
+        collect_metadata,
+    )
+    .await
+}
+
+/// Read contents of picked `file` and decode them with `codec`.
+async fn load_picked_file<T: LoadContents>(
+    file: &FileHandle,
+    max_size: Option<u64>,
+    verify_content: bool,
+    codec: &Codec,
+    #[cfg(not(target_arch = "wasm32"))] collect_metadata: bool,
+) -> DialogOutcome<T> {
+    let contents = match read_limited(file, max_size).await {
+        Ok(contents) => contents,
+        Err(event) => return DialogOutcome::TooLarge(event),
+    };
+
+    decode_loaded(
+        file,
+        contents,
+        max_size,
+        verify_content,
+        codec,
+        #[cfg(not(target_arch = "wasm32"))]
+        collect_metadata,
+    )
+}
+
+/// Verify and decode `contents` read from `file` with `codec`, decoded
+/// contents are limited to `max_size` as well.
+pub(crate) fn decode_loaded<T: LoadContents>(
+    file: &FileHandle,
+    contents: Vec<u8>,
+    max_size: Option<u64>,
+    verify_content: bool,
+    codec: &Codec,
+    #[cfg(not(target_arch = "wasm32"))] collect_metadata: bool,
+) -> DialogOutcome<T> {
+    let contents = match codec.decode(file, contents, max_size) {
+        Ok(Ok(contents)) => contents,
+        Ok(Err(size)) => {
+            let max_size = max_size.unwrap_or_default();
+            return DialogOutcome::TooLarge(DialogFileTooLarge::new(file, size, max_size));
+        }
+        Err(error) => return DialogOutcome::Failed(DialogFileLoadFailed::new(file, error)),
+    };
+
+    // Decoded contents are checked, so that encrypted or compressed files
+    // don't fail the check
+    let file_name = file.file_name();
+    let content_type = match sniff(verify_content, codec.decoded_name(&file_name), &contents) {
+        Ok(content_type) => content_type,
+        Err(error) => return DialogOutcome::Failed(DialogFileLoadFailed::new(file, error)),
+    };
+
+    DialogOutcome::Loaded(DialogFileLoaded {
+        file_name: file.file_name(),
+        content_type,
+        #[cfg(not(target_arch = "wasm32"))]
+        metadata: FileMetadata::collect(collect_metadata, file.path(), Some(&contents)),
+        contents: Arc::new(contents),
+        #[cfg(not(target_arch = "wasm32"))]
+        path: file.path().to_path_buf(),
+        marker: PhantomData,
+    })
+}
+
 /// Event that gets sent when user closes file load dialog without picking any file.
 #[derive(Message)]
 pub struct DialogFileLoadCanceled<T: LoadContents>(PhantomData<T>);
@@ -245,10 +720,7 @@ impl<T: SaveContents> Default for DialogFileSaveCanceled<T> {
 
 impl Plugin for FileDialogPlugin {
     fn build(&self, app: &mut App) {
-        assert!(
-            !self.0.is_empty(),
-            "File dialog not initialized, use at least one FileDialogPlugin::with_*"
-        );
+        callback::register_dialog_callbacks(app);
 
         for action in &self.0 {
             action(app);
@@ -261,6 +733,44 @@ impl Plugin for FileDialogPlugin {
 pub struct FileDialog<'w, 's, 'a> {
     commands: &'a mut Commands<'w, 's>,
     dialog: AsyncFileDialog,
+    extensions: Vec<String>,
+    config: DialogConfig,
+    load_options: LoadOptions,
+    codec: Codec,
+    #[cfg(not(target_arch = "wasm32"))]
+    collect_metadata: bool,
+}
+
+/// Options that affect how file contents get loaded after the file is picked.
+struct LoadOptions {
+    max_size: Option<u64>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    range: Option<std::ops::Range<u64>>,
+
+    #[cfg(all(feature = "mmap", not(target_arch = "wasm32")))]
+    memory_map: bool,
+
+    verify_content: bool,
+
+    max_concurrent_loads: usize,
+
+    send_as_loaded: bool,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            max_size: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            range: None,
+            #[cfg(all(feature = "mmap", not(target_arch = "wasm32")))]
+            memory_map: false,
+            verify_content: false,
+            max_concurrent_loads: 1,
+            send_as_loaded: false,
+        }
+    }
 }
 
 impl FileDialog<'_, '_, '_> {
@@ -274,7 +784,10 @@ impl FileDialog<'_, '_, '_> {
     ///
     /// On platforms that don't support filter names, all filters will be merged into one filter
     pub fn add_filter(mut self, name: impl Into<String>, extensions: &[impl ToString]) -> Self {
+        self.extensions
+            .extend(extensions.iter().map(ToString::to_string));
         self.dialog = self.dialog.add_filter(name, extensions);
+        self.config.has_filters = true;
         self
     }
 
@@ -284,6 +797,7 @@ impl FileDialog<'_, '_, '_> {
     ///   * Mac
     pub fn set_directory<P: AsRef<Path>>(mut self, path: P) -> Self {
         self.dialog = self.dialog.set_directory(path);
+        self.config.has_directory = true;
         self
     }
 
@@ -293,6 +807,7 @@ impl FileDialog<'_, '_, '_> {
     ///  * Mac
     pub fn set_file_name(mut self, file_name: impl Into<String>) -> Self {
         self.dialog = self.dialog.set_file_name(file_name);
+        self.config.has_file_name = true;
         self
     }
 
@@ -303,6 +818,83 @@ impl FileDialog<'_, '_, '_> {
     ///  * WASM32
     pub fn set_title(mut self, title: impl Into<String>) -> Self {
         self.dialog = self.dialog.set_title(title);
+        self.config.has_title = true;
+        self
+    }
+
+    /// Limit size of files loaded with this dialog, overriding limit set with
+    /// [`FileDialogPlugin::with_max_file_size`]. When user picks file larger
+    /// than `max_size` bytes, its contents are not read and
+    /// [`DialogFileTooLarge<T>`] gets sent instead.
+    pub fn set_max_file_size(mut self, max_size: u64) -> Self {
+        self.load_options.max_size = Some(max_size);
+        self
+    }
+
+    /// Read up to `limit` files picked with
+    /// [`FileDialog::load_multiple_files`] at the same time on the
+    /// [`IoTaskPool`], which makes loading many files faster. With the
+    /// default limit of 1 files are read one after another.
+    pub fn set_max_concurrent_loads(mut self, limit: usize) -> Self {
+        self.load_options.max_concurrent_loads = limit;
+        self
+    }
+
+    /// Send [`DialogFileLoaded<T>`] for each file picked with
+    /// [`FileDialog::load_multiple_files`] as soon as it gets loaded,
+    /// instead of sending all of them together after the last file is
+    /// loaded. With [`FileDialog::set_max_concurrent_loads`] the files may
+    /// arrive in different order than they were selected in.
+    ///
+    /// [`DialogFilesLoaded<T>`] sent when
+    /// [`FileDialogPlugin::with_batch_messages::<T>`] is used still contains
+    /// all loaded files and gets sent after the last file is loaded.
+    pub fn set_send_as_loaded(mut self, send_as_loaded: bool) -> Self {
+        self.load_options.send_as_loaded = send_as_loaded;
+        self
+    }
+
+    /// Compress file contents when saving and decompress them when loading
+    /// with this dialog. Applies to [`FileDialog::save_file`],
+    /// [`FileDialog::save_file_with`], [`FileDialog::save_file_from_world`],
+    /// [`FileDialog::load_file`] and [`FileDialog::load_multiple_files`].
+    ///
+    /// When loaded file can't be decompressed, [`DialogFileLoadFailed<T>`]
+    /// gets sent instead of [`DialogFileLoaded<T>`].
+    pub fn set_compression(mut self, compression: Compression) -> Self {
+        self.codec.compression = compression;
+        self
+    }
+
+    /// Encrypt file contents with key derived from `password` when saving
+    /// and decrypt them when loading with this dialog. Applies to the same
+    /// functions as [`FileDialog::set_compression`], contents are compressed
+    /// before they are encrypted.
+    ///
+    /// When loaded file can't be decrypted because the password is wrong or
+    /// the file was tampered with, [`DialogFileLoadFailed<T>`] gets sent with
+    /// [`LoadError::Decrypt`].
+    ///
+    /// Requires `encryption` feature.
+    #[cfg(feature = "encryption")]
+    pub fn set_password(mut self, password: impl Into<String>) -> Self {
+        self.codec.password = Some(password.into());
+        self
+    }
+
+    /// Store checksum of saved file and verify it when loading with this
+    /// dialog. Applies to the same functions as
+    /// [`FileDialog::set_compression`], the checksum is computed from the
+    /// final contents written to the file.
+    ///
+    /// When loaded file does not match its checksum,
+    /// [`DialogFileLoadFailed<T>`] gets sent with
+    /// [`LoadError::ChecksumMismatch`].
+    ///
+    /// Requires `checksum` feature.
+    #[cfg(feature = "checksum")]
+    pub fn set_checksum(mut self, checksum: Checksum) -> Self {
+        self.codec.checksum = checksum;
         self
     }
 
@@ -310,38 +902,17 @@ impl FileDialog<'_, '_, '_> {
     /// gets saved, the [`DialogFileSaved<T>`] gets sent. You can get read this event
     /// with Bevy's [`EventReader<DialogFileSaved<T>>`] system param.
     pub fn save_file<T: SaveContents>(self, contents: Vec<u8>) {
-        self.commands.queue(|world: &mut World| {
+        self.open_save_file(contents, |world| {
             let sender = world
                 .get_resource::<StreamSender<DialogResult<DialogFileSaved<T>>>>()
                 .expect("FileDialogPlugin not initialized with 'with_save_file::<T>()'")
                 .0
                 .clone();
 
-            let event_loop_proxy = world
-                .get_resource::<EventLoopProxyWrapper>()
-                .map(|proxy| EventLoopProxy::clone(&**proxy));
-
-            AsyncComputeTaskPool::get()
-                .spawn(async move {
-                    let file = self.dialog.save_file().await;
-                    let _wake_up = event_loop_proxy.as_ref().map(WakeUpOnDrop);
-
-                    let Some(file) = file else {
-                        sender.send(DialogResult::Canceled).unwrap();
-                        return;
-                    };
-
-                    let event = DialogFileSaved {
-                        file_name: file.file_name(),
-                        result: file.write(&contents).await,
-                        #[cfg(not(target_arch = "wasm32"))]
-                        path: file.path().to_path_buf(),
-                        marker: PhantomData,
-                    };
-
-                    sender.send(DialogResult::Single(event)).unwrap();
-                })
-                .detach();
+            move |outcome| match outcome {
+                DialogOutcome::Saved(event) => sender.send(DialogResult::Single(event)).unwrap(),
+                _ => sender.send(DialogResult::Canceled).unwrap(),
+            }
         });
     }
 
@@ -349,38 +920,31 @@ impl FileDialog<'_, '_, '_> {
     /// loaded, the [`DialogFileLoaded<T>`] gets sent. You can read this event with
     /// Bevy's [`EventReader<DialogFileLoaded<T>>`].
     pub fn load_file<T: LoadContents>(self) {
-        self.commands.queue(|world: &mut World| {
+        self.open_load_file(|world| {
             let sender = world
                 .get_resource::<StreamSender<DialogResult<DialogFileLoaded<T>>>>()
                 .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
                 .0
                 .clone();
 
-            let event_loop_proxy = world
-                .get_resource::<EventLoopProxyWrapper>()
-                .map(|proxy| EventLoopProxy::clone(&**proxy));
-
-            AsyncComputeTaskPool::get()
-                .spawn(async move {
-                    let file = self.dialog.pick_file().await;
-                    let _wake_up = event_loop_proxy.as_ref().map(WakeUpOnDrop);
-
-                    let Some(file) = file else {
-                        sender.send(DialogResult::Canceled).unwrap();
-                        return;
-                    };
+            let too_large_sender = world
+                .get_resource::<StreamSender<DialogFileTooLarge<T>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
 
-                    let event = DialogFileLoaded {
-                        file_name: file.file_name(),
-                        contents: file.read().await,
-                        #[cfg(not(target_arch = "wasm32"))]
-                        path: file.path().to_path_buf(),
-                        marker: PhantomData,
-                    };
+            let failed_sender = world
+                .get_resource::<StreamSender<DialogFileLoadFailed<T>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
 
-                    sender.send(DialogResult::Single(event)).unwrap();
-                })
-                .detach();
+            move |outcome| match outcome {
+                DialogOutcome::Loaded(event) => sender.send(DialogResult::Single(event)).unwrap(),
+                DialogOutcome::TooLarge(event) => too_large_sender.send(event).unwrap(),
+                DialogOutcome::Failed(event) => failed_sender.send(event).unwrap(),
+                _ => sender.send(DialogResult::Canceled).unwrap(),
+            }
         });
     }
 
@@ -389,37 +953,126 @@ impl FileDialog<'_, '_, '_> {
     /// [`DialogFileLoaded<T>`] gets sent for each file. You can read each file
     /// by reading every event received with with Bevy's
     /// [`EventReader<DialogFileLoaded<T>>`].
+    ///
+    /// When [`FileDialogPlugin::with_batch_messages::<T>`] is used, single
+    /// [`DialogFilesLoaded<T>`] with all files gets sent as well.
+    ///
+    /// See [`FileDialog::set_max_concurrent_loads`] and
+    /// [`FileDialog::set_send_as_loaded`] for loading many files faster.
     pub fn load_multiple_files<T: LoadContents>(self) {
-        self.commands.queue(|world: &mut World| {
+        self.commands.queue(move |world: &mut World| {
             let sender = world
                 .get_resource::<StreamSender<DialogResult<DialogFileLoaded<T>>>>()
                 .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
                 .0
                 .clone();
 
-            let event_loop_proxy = world
-                .get_resource::<EventLoopProxyWrapper>()
-                .map(|proxy| EventLoopProxy::clone(&**proxy));
+            let too_large_sender = world
+                .get_resource::<StreamSender<DialogFileTooLarge<T>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
+
+            let failed_sender = world
+                .get_resource::<StreamSender<DialogFileLoadFailed<T>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
+
+            let max_size = self.load_options.max_size.or_else(|| {
+                world
+                    .get_resource::<MaxFileSize<T>>()
+                    .map(|max_size| max_size.0)
+            });
+
+            let waker = AppWaker::from_world(world);
+
+            let batch_sender = world
+                .get_resource::<StreamSender<DialogFilesLoaded<T>>>()
+                .map(|sender| sender.0.clone());
+
+            let codec = self.codec.with_format::<T>(world);
+
+            let dialog = self.config.apply::<T>(self.dialog, world);
 
             AsyncComputeTaskPool::get()
                 .spawn(async move {
-                    let files = AsyncFileDialog::new().pick_files().await;
-                    let _wake_up = event_loop_proxy.as_ref().map(WakeUpOnDrop);
+                    let files = dialog.pick_files().await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
 
                     let Some(files) = files else {
                         sender.send(DialogResult::Canceled).unwrap();
                         return;
                     };
 
-                    let mut events = Vec::new();
-                    for file in files {
-                        events.push(DialogFileLoaded {
-                            file_name: file.file_name(),
-                            contents: file.read().await,
-                            #[cfg(not(target_arch = "wasm32"))]
-                            path: file.path().to_path_buf(),
-                            marker: PhantomData,
-                        });
+                    let send_as_loaded = self.load_options.send_as_loaded;
+                    let collect_batch = !send_as_loaded || batch_sender.is_some();
+                    let verify_content = self.load_options.verify_content;
+                    #[cfg(not(target_arch = "wasm32"))]
+                    let collect_metadata = self.collect_metadata;
+
+                    let mut loaded = Vec::new();
+                    load_concurrently(
+                        files,
+                        self.load_options.max_concurrent_loads,
+                        move |file| {
+                            let codec = codec.clone();
+                            async move {
+                                load_picked_file::<T>(
+                                    &file,
+                                    max_size,
+                                    verify_content,
+                                    &codec,
+                                    #[cfg(not(target_arch = "wasm32"))]
+                                    collect_metadata,
+                                )
+                                .await
+                            }
+                        },
+                        |index, outcome| {
+                            match outcome {
+                                DialogOutcome::Loaded(event) if send_as_loaded => {
+                                    if collect_batch {
+                                        loaded.push((index, event.clone()));
+                                    }
+
+                                    sender.send(DialogResult::Single(event)).unwrap();
+                                }
+                                DialogOutcome::Loaded(event) => loaded.push((index, event)),
+                                DialogOutcome::TooLarge(event) => {
+                                    too_large_sender.send(event).unwrap()
+                                }
+                                DialogOutcome::Failed(event) => failed_sender.send(event).unwrap(),
+                                _ => {}
+                            }
+
+                            if send_as_loaded {
+                                // Don't wait for the rest of the files to wake up the app
+                                drop(waker.as_ref().map(WakeUpOnDrop));
+                            }
+                        },
+                    )
+                    .await;
+
+                    if !collect_batch {
+                        return;
+                    }
+
+                    // Files are loaded in any order, send them in the order they were selected
+                    loaded.sort_by_key(|(index, _)| *index);
+                    let events: Vec<_> = loaded.into_iter().map(|(_, event)| event).collect();
+
+                    if send_as_loaded {
+                        if let Some(batch_sender) = batch_sender {
+                            batch_sender.send(DialogFilesLoaded::new(events)).unwrap();
+                        }
+                        return;
+                    }
+
+                    if let Some(batch_sender) = batch_sender {
+                        batch_sender
+                            .send(DialogFilesLoaded::new(events.clone()))
+                            .unwrap();
                     }
 
                     sender.send(DialogResult::Batch(events)).unwrap();
@@ -441,16 +1094,199 @@ impl<'w, 's> FileDialogExt<'w, 's> for Commands<'w, 's> {
         FileDialog {
             commands: self,
             dialog: AsyncFileDialog::new(),
+            extensions: Vec::new(),
+            config: DialogConfig::default(),
+            load_options: LoadOptions::default(),
+            codec: Codec::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            collect_metadata: false,
+        }
+    }
+}
+
+/// Wakes up the app, so that it handles events sent from dialog tasks even
+/// when it waits for input.
+#[derive(Clone)]
+enum AppWaker {
+    Winit(EventLoopProxy<WinitUserEvent>),
+    #[cfg(all(test, not(target_arch = "wasm32")))]
+    Count(std::sync::Arc<std::sync::atomic::AtomicUsize>),
+}
+
+impl AppWaker {
+    /// Waker of the app that owns `world`, if the app can be woken up.
+    fn from_world(world: &World) -> Option<Self> {
+        #[cfg(all(test, not(target_arch = "wasm32")))]
+        if let Some(wake_ups) = world.get_resource::<testing::WakeUps>() {
+            return Some(Self::Count(wake_ups.0.clone()));
+        }
+
+        world
+            .get_resource::<EventLoopProxyWrapper>()
+            .map(|proxy| Self::Winit(EventLoopProxy::clone(&**proxy)))
+    }
+
+    fn wake_up(&self) {
+        match self {
+            Self::Winit(proxy) => proxy.send_event(WinitUserEvent::WakeUp).unwrap(),
+            #[cfg(all(test, not(target_arch = "wasm32")))]
+            Self::Count(count) => {
+                count.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            }
         }
     }
 }
 
 /// A struct to send a WakeUp event to winit when dropped (i.e., when the scope
 /// ends).
-struct WakeUpOnDrop<'a>(&'a EventLoopProxy<WinitUserEvent>);
+struct WakeUpOnDrop<'a>(&'a AppWaker);
 
 impl Drop for WakeUpOnDrop<'_> {
     fn drop(&mut self) {
-        self.0.send_event(WinitUserEvent::WakeUp).unwrap();
+        self.0.wake_up();
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    fn decode(codec: &Codec, name: &str, contents: &[u8], verify: bool) -> DialogOutcome<()> {
+        let file = FileHandle::from(std::path::PathBuf::from(name));
+        let contents = codec.encode(&file, contents.to_vec()).unwrap();
+        decode_loaded(&file, contents, Some(100), verify, codec, false)
+    }
+
+    #[test]
+    fn verify_plain_contents() {
+        let codec = Codec::default();
+
+        let outcome = decode(&codec, "level.json", b"{}", true);
+        assert!(matches!(
+            outcome,
+            DialogOutcome::Loaded(event) if event.content_type == Some(ContentType::Json)
+        ));
+
+        let outcome = decode(&codec, "level.png", b"{}", true);
+        assert!(matches!(
+            outcome,
+            DialogOutcome::Failed(DialogFileLoadFailed {
+                error: LoadError::ContentMismatch { .. },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn verify_decoded_contents() {
+        let codec = Codec {
+            format: Some(std::sync::Arc::new(VersionedFormat::new(*b"LVL", 1))),
+            ..Default::default()
+        };
+
+        // Header of the format is not part of the checked contents
+        let outcome = decode(&codec, "level.json", b"{}", true);
+        assert!(matches!(
+            outcome,
+            DialogOutcome::Loaded(event) if *event.contents == b"{}"
+        ));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn verify_decompressed_contents() {
+        let codec = Codec {
+            compression: Compression::FromExtension,
+            ..Default::default()
+        };
+
+        let outcome = decode(&codec, "level.json.gz", b"{}", true);
+        assert!(matches!(
+            outcome,
+            DialogOutcome::Loaded(event) if event.content_type == Some(ContentType::Json)
+        ));
+
+        let outcome = decode(&codec, "level.json.gz", &[b' '; 1000], false);
+        assert!(matches!(
+            outcome,
+            DialogOutcome::TooLarge(event) if event.size == 101 && event.max_size == 100
+        ));
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn verify_decrypted_contents() {
+        let codec = Codec {
+            password: Some("password".to_string()),
+            ..Default::default()
+        };
+
+        let outcome = decode(&codec, "level.json", b"{}", true);
+        assert!(matches!(
+            outcome,
+            DialogOutcome::Loaded(event) if event.content_type == Some(ContentType::Json)
+        ));
+    }
+
+    /// Second file is a pipe, so that it gets loaded only after the test
+    /// writes into it.
+    #[cfg(unix)]
+    #[test]
+    fn files_sent_as_loaded() {
+        use testing::{collect, received, respond, update_until, WakeUps};
+
+        struct Notes;
+
+        let directory = testing::test_directory("send_as_loaded");
+        let first = directory.join("first.txt");
+        std::fs::write(&first, b"first").unwrap();
+        let second = directory.join("second.txt");
+        let status = std::process::Command::new("mkfifo")
+            .arg(&second)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let mut app = testing::app(FileDialogPlugin::new().with_load_file::<Notes>());
+        app.init_resource::<WakeUps>();
+        collect::<DialogFileLoaded<Notes>>(&mut app);
+        respond(&mut app, [&first, &second]);
+
+        app.world_mut()
+            .commands()
+            .dialog()
+            .set_send_as_loaded(true)
+            .load_multiple_files::<Notes>();
+
+        let loaded = |world: &World| {
+            world
+                .resource::<testing::Received<DialogFileLoaded<Notes>>>()
+                .0
+                .len()
+        };
+
+        let wake_ups = |world: &World| world.resource::<WakeUps>().count();
+
+        // The task waits for the second file, but the first one is sent and
+        // the app woken up already
+        update_until(&mut app, |world| loaded(world) == 1 && wake_ups(world) == 1);
+        assert_eq!(
+            received::<DialogFileLoaded<Notes>>(&app)[0].file_name,
+            "first.txt"
+        );
+        assert_eq!(wake_ups(app.world()), 1);
+
+        let writer = std::thread::spawn(move || std::fs::write(second, b"second").unwrap());
+        update_until(&mut app, |world| loaded(world) == 2 && wake_ups(world) >= 2);
+        writer.join().unwrap();
+
+        assert_eq!(
+            received::<DialogFileLoaded<Notes>>(&app)[1].file_name,
+            "second.txt"
+        );
+        assert_eq!(
+            *received::<DialogFileLoaded<Notes>>(&app)[1].contents,
+            b"second"
+        );
     }
 }
@@ -0,0 +1,123 @@
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::FileDialog;
+
+/// Metadata of picked or loaded file, collected when
+/// [`FileDialog::set_collect_metadata`] is enabled. Hash of the contents is
+/// only collected with `checksum` feature.
+#[derive(Clone, Debug)]
+pub struct FileMetadata {
+    /// Size of the file in bytes.
+    pub size: u64,
+
+    /// Last modification time, if supported by the platform.
+    pub modified: Option<SystemTime>,
+
+    /// Creation time, if supported by the platform.
+    pub created: Option<SystemTime>,
+
+    /// Whether the file is read-only.
+    pub read_only: bool,
+
+    /// SHA-256 hash of loaded contents, it is only present for loaded files.
+    ///
+    /// Requires `checksum` feature.
+    #[cfg(feature = "checksum")]
+    pub sha256: Option<[u8; 32]>,
+}
+
+impl FileMetadata {
+    /// Read metadata of file at `path`, `contents` are hashed when present.
+    /// Returns `None` if metadata of the file can't be read.
+    #[cfg_attr(not(feature = "checksum"), allow(unused_variables))]
+    pub(crate) fn read(path: &Path, contents: Option<&[u8]>) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+
+        Some(Self {
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            created: metadata.created().ok(),
+            read_only: metadata.permissions().readonly(),
+            #[cfg(feature = "checksum")]
+            sha256: contents.map(|contents| {
+                use sha2::Digest;
+
+                sha2::Sha256::digest(contents).into()
+            }),
+        })
+    }
+
+    /// Same as [`FileMetadata::read`], but only when `collect` is `true`.
+    pub(crate) fn collect(collect: bool, path: &Path, contents: Option<&[u8]>) -> Option<Self> {
+        if collect {
+            Self::read(path, contents)
+        } else {
+            None
+        }
+    }
+}
+
+impl FileDialog<'_, '_, '_> {
+    /// Collect [`FileMetadata`] of picked and loaded files. The metadata is
+    /// read on the task pool together with the file, so it does not block the
+    /// app, and is available in `metadata` field of
+    /// [`DialogFileLoaded<T>`](crate::DialogFileLoaded) and
+    /// [`DialogFilePicked<T>`](crate::DialogFilePicked).
+    ///
+    /// SHA-256 hash of loaded contents is part of the metadata only with
+    /// `checksum` feature, without it only the file system metadata is
+    /// collected.
+    ///
+    /// Does not exist in `wasm32`.
+    pub fn set_collect_metadata(mut self, collect: bool) -> Self {
+        self.collect_metadata = collect;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::test_directory;
+
+    #[test]
+    fn read_metadata() {
+        let path = test_directory("metadata").join("file.txt");
+        fs::write(&path, b"contents").unwrap();
+
+        let metadata = FileMetadata::read(&path, None).unwrap();
+        assert_eq!(metadata.size, 8);
+        assert!(!metadata.read_only);
+        assert!(metadata.modified.is_some());
+        #[cfg(feature = "checksum")]
+        assert_eq!(metadata.sha256, None);
+    }
+
+    #[test]
+    fn read_missing() {
+        let path = test_directory("metadata_missing").join("missing.txt");
+        assert!(FileMetadata::read(&path, None).is_none());
+    }
+
+    #[test]
+    fn collect_only_when_enabled() {
+        let path = test_directory("metadata_collect").join("file.txt");
+        fs::write(&path, b"contents").unwrap();
+
+        assert!(FileMetadata::collect(false, &path, None).is_none());
+        assert!(FileMetadata::collect(true, &path, None).is_some());
+    }
+
+    #[cfg(feature = "checksum")]
+    #[test]
+    fn hash_contents() {
+        let path = test_directory("metadata_hash").join("file.txt");
+        fs::write(&path, b"abc").unwrap();
+
+        let metadata = FileMetadata::read(&path, Some(b"abc")).unwrap();
+        let sha256 = metadata.sha256.unwrap();
+        assert_eq!(sha256[..4], [0xba, 0x78, 0x16, 0xbf]);
+    }
+}
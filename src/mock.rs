@@ -0,0 +1,61 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
+
+use bevy_ecs::prelude::*;
+
+/// Resource that answers opened dialogs with queued paths instead of showing
+/// native dialogs, useful for testing systems and remote clients that open
+/// dialogs.
+///
+/// Each opened dialog takes the next queued response, single file and
+/// directory dialogs use the first path of the response. When there is no
+/// queued response, the dialog gets canceled.
+///
+/// ```rust
+/// fn setup(mut backend: ResMut<MockDialogBackend>) {
+///     backend.push_pick("save.txt");
+///     backend.push_cancel();
+/// }
+/// ```
+///
+/// Does not exist in `wasm32`.
+#[derive(Resource, Default)]
+pub struct MockDialogBackend {
+    responses: Mutex<VecDeque<Vec<PathBuf>>>,
+}
+
+impl MockDialogBackend {
+    /// Answer the next dialog with `path`.
+    pub fn push_pick(&mut self, path: impl Into<PathBuf>) {
+        self.push_pick_multiple([path]);
+    }
+
+    /// Answer the next dialog with all `paths`, for dialogs that pick
+    /// multiple files or directories.
+    pub fn push_pick_multiple(&mut self, paths: impl IntoIterator<Item = impl Into<PathBuf>>) {
+        let paths = paths.into_iter().map(Into::into).collect();
+        self.responses().push_back(paths);
+    }
+
+    /// Cancel the next dialog.
+    pub fn push_cancel(&mut self) {
+        self.responses().push_back(Vec::new());
+    }
+
+    /// Take response for dialog that is being opened, empty when the dialog
+    /// should be canceled.
+    pub(crate) fn next(&self) -> Vec<PathBuf> {
+        self.responses
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front()
+            .unwrap_or_default()
+    }
+
+    fn responses(&mut self) -> &mut VecDeque<Vec<PathBuf>> {
+        self.responses
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
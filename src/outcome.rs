@@ -0,0 +1,206 @@
+use bevy_ecs::prelude::*;
+
+use crate::{DialogFileLoadFailed, DialogFileLoaded, DialogFileSaved, DialogFileTooLarge};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{DialogDirectoryPicked, DialogFilePicked};
+
+/// Outcome of single dialog opened with marker `T`, combines all events that
+/// the dialog can send into one type.
+///
+/// It is returned by the `*_async` functions and passed to callbacks of the
+/// `*_then` functions of [`FileDialog`](crate::FileDialog). It can be also
+/// sent as event instead of the other events of marker `T`, see
+/// [`FileDialogPlugin::with_outcome_message`](crate::FileDialogPlugin::with_outcome_message).
+#[derive(Message)]
+#[non_exhaustive]
+pub enum DialogOutcome<T: Send + Sync + 'static> {
+    /// File contents were loaded, same as [`DialogFileLoaded<T>`].
+    Loaded(DialogFileLoaded<T>),
+
+    /// File contents were saved, same as [`DialogFileSaved<T>`].
+    Saved(DialogFileSaved<T>),
+
+    /// File path was picked, same as [`DialogFilePicked<T>`].
+    ///
+    /// Does not exist in wasm, you can use this on native platforms only.
+    #[cfg(not(target_arch = "wasm32"))]
+    Picked(DialogFilePicked<T>),
+
+    /// Directory path was picked, same as [`DialogDirectoryPicked<T>`].
+    ///
+    /// Does not exist in wasm, you can use this on native platforms only.
+    #[cfg(not(target_arch = "wasm32"))]
+    DirectoryPicked(DialogDirectoryPicked<T>),
+
+    /// Picked file was larger than the size limit, same as
+    /// [`DialogFileTooLarge<T>`].
+    TooLarge(DialogFileTooLarge<T>),
+
+    /// Picked file could not be decoded, same as
+    /// [`DialogFileLoadFailed<T>`].
+    Failed(DialogFileLoadFailed<T>),
+
+    /// User closed the dialog without picking any file or directory.
+    Canceled,
+}
+
+/// Event that can be sent as [`DialogOutcome`] instead.
+pub(crate) trait IntoOutcome: Message {
+    type Marker: Send + Sync + 'static;
+
+    fn into_outcome(self) -> DialogOutcome<Self::Marker>;
+}
+
+impl<T: Send + Sync + 'static> IntoOutcome for DialogFileLoaded<T> {
+    type Marker = T;
+
+    fn into_outcome(self) -> DialogOutcome<T> {
+        DialogOutcome::Loaded(self)
+    }
+}
+
+impl<T: Send + Sync + 'static> IntoOutcome for DialogFileSaved<T> {
+    type Marker = T;
+
+    fn into_outcome(self) -> DialogOutcome<T> {
+        DialogOutcome::Saved(self)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send + Sync + 'static> IntoOutcome for DialogFilePicked<T> {
+    type Marker = T;
+
+    fn into_outcome(self) -> DialogOutcome<T> {
+        DialogOutcome::Picked(self)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send + Sync + 'static> IntoOutcome for DialogDirectoryPicked<T> {
+    type Marker = T;
+
+    fn into_outcome(self) -> DialogOutcome<T> {
+        DialogOutcome::DirectoryPicked(self)
+    }
+}
+
+impl<T: Send + Sync + 'static> IntoOutcome for DialogFileTooLarge<T> {
+    type Marker = T;
+
+    fn into_outcome(self) -> DialogOutcome<T> {
+        DialogOutcome::TooLarge(self)
+    }
+}
+
+impl<T: Send + Sync + 'static> IntoOutcome for DialogFileLoadFailed<T> {
+    type Marker = T;
+
+    fn into_outcome(self) -> DialogOutcome<T> {
+        DialogOutcome::Failed(self)
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::testing::{self, collect, received, respond, test_directory, update_until};
+    use crate::{DialogFileLoadCanceled, DialogFileSaveCanceled, FileDialogExt, FileDialogPlugin};
+
+    struct Notes;
+
+    fn describe(outcome: &DialogOutcome<Notes>) -> String {
+        match outcome {
+            DialogOutcome::Loaded(loaded) => format!("loaded {}", loaded.file_name),
+            DialogOutcome::Saved(saved) => format!("saved {}", saved.file_name),
+            DialogOutcome::Picked(picked) => format!("picked {}", picked.path.display()),
+            DialogOutcome::DirectoryPicked(picked) => {
+                format!("directory {}", picked.path.display())
+            }
+            DialogOutcome::TooLarge(too_large) => format!("too large {}", too_large.file_name),
+            DialogOutcome::Failed(failed) => format!("failed {}", failed.file_name),
+            DialogOutcome::Canceled => "canceled".to_string(),
+        }
+    }
+
+    #[test]
+    fn outcome_sent_instead_of_events() {
+        let directory = test_directory("outcome_message");
+        let notes = directory.join("notes.txt");
+        fs::write(&notes, b"notes").unwrap();
+        let image = directory.join("image.png");
+        fs::write(&image, b"not an image").unwrap();
+
+        let mut app = testing::app(
+            FileDialogPlugin::new()
+                .with_load_file::<Notes>()
+                .with_save_file::<Notes>()
+                .with_pick_file::<Notes>()
+                .with_outcome_message::<Notes>(),
+        );
+        collect::<DialogOutcome<Notes>>(&mut app);
+        collect::<DialogFileLoaded<Notes>>(&mut app);
+        collect::<DialogFileSaved<Notes>>(&mut app);
+        collect::<DialogFilePicked<Notes>>(&mut app);
+        collect::<DialogFileTooLarge<Notes>>(&mut app);
+        collect::<DialogFileLoadFailed<Notes>>(&mut app);
+        collect::<DialogFileLoadCanceled<Notes>>(&mut app);
+        collect::<DialogFileSaveCanceled<Notes>>(&mut app);
+
+        respond(&mut app, [&notes]);
+        respond(&mut app, [&notes]);
+        respond(&mut app, [&notes]);
+        respond(&mut app, [&image]);
+        respond(&mut app, Vec::<&str>::new());
+        respond(&mut app, [directory.join("saved.txt")]);
+
+        let mut commands = app.world_mut().commands();
+        commands.dialog().load_file::<Notes>();
+        commands.dialog().pick_file_path::<Notes>();
+        commands.dialog().set_max_file_size(2).load_file::<Notes>();
+        commands
+            .dialog()
+            .set_verify_content(true)
+            .load_file::<Notes>();
+        commands.dialog().load_file::<Notes>();
+        commands.dialog().save_file::<Notes>(b"saved".to_vec());
+
+        update_until(&mut app, |world| {
+            world
+                .resource::<testing::Received<DialogOutcome<Notes>>>()
+                .0
+                .len()
+                >= 6
+        });
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let mut outcomes: Vec<_> = received::<DialogOutcome<Notes>>(&app)
+            .iter()
+            .map(describe)
+            .collect();
+        outcomes.sort();
+        let mut expected = vec![
+            "loaded notes.txt".to_string(),
+            format!("picked {}", notes.display()),
+            "too large notes.txt".to_string(),
+            "failed image.png".to_string(),
+            "canceled".to_string(),
+            "saved saved.txt".to_string(),
+        ];
+        expected.sort();
+        assert_eq!(outcomes, expected);
+
+        assert!(received::<DialogFileLoaded<Notes>>(&app).is_empty());
+        assert!(received::<DialogFileSaved<Notes>>(&app).is_empty());
+        assert!(received::<DialogFilePicked<Notes>>(&app).is_empty());
+        assert!(received::<DialogFileTooLarge<Notes>>(&app).is_empty());
+        assert!(received::<DialogFileLoadFailed<Notes>>(&app).is_empty());
+        assert!(received::<DialogFileLoadCanceled<Notes>>(&app).is_empty());
+        assert!(received::<DialogFileSaveCanceled<Notes>>(&app).is_empty());
+    }
+}
@@ -0,0 +1,104 @@
+use std::future::Future;
+
+use bevy_tasks::prelude::*;
+use bevy_tasks::ConditionalSend;
+use rfd::FileHandle;
+
+/// Load `files` with `load`, running at most `limit` loads at the same time
+/// on the [`IoTaskPool`]. `on_loaded` gets called with the index of the file
+/// in `files` as soon as its load completes, so files may complete in any
+/// order.
+///
+/// With `limit` of 0 or 1 the files get loaded one after another in the
+/// current task instead.
+pub(crate) async fn load_concurrently<F, Fut, O>(
+    files: Vec<FileHandle>,
+    limit: usize,
+    load: F,
+    mut on_loaded: impl FnMut(usize, O),
+) where
+    F: Fn(FileHandle) -> Fut + Clone + ConditionalSend + 'static,
+    Fut: Future<Output = O> + ConditionalSend + 'static,
+    O: ConditionalSend + 'static,
+{
+    if limit <= 1 {
+        for (index, file) in files.into_iter().enumerate() {
+            on_loaded(index, load(file).await);
+        }
+        return;
+    }
+
+    let workers = limit.min(files.len());
+
+    let (queue_tx, queue_rx) = async_channel::unbounded();
+    for file in files.into_iter().enumerate() {
+        // Unbounded channel with living receiver never fails
+        queue_tx.try_send(file).unwrap();
+    }
+    // Workers stop once the queue is empty and closed
+    drop(queue_tx);
+
+    let (done_tx, done_rx) = async_channel::unbounded();
+    for _ in 0..workers {
+        let queue_rx = queue_rx.clone();
+        let done_tx = done_tx.clone();
+        let load = load.clone();
+
+        IoTaskPool::get()
+            .spawn(async move {
+                while let Ok((index, file)) = queue_rx.recv().await {
+                    let outcome = load(file).await;
+                    if done_tx.send((index, outcome)).await.is_err() {
+                        // Nobody is waiting for the rest of the files
+                        break;
+                    }
+                }
+            })
+            .detach();
+    }
+    drop(done_tx);
+
+    while let Ok((index, outcome)) = done_rx.recv().await {
+        on_loaded(index, outcome);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::path::PathBuf;
+
+    use bevy_tasks::{block_on, TaskPool};
+
+    use super::*;
+
+    fn load_all(limit: usize) -> Vec<(usize, String)> {
+        IoTaskPool::get_or_init(TaskPool::new);
+
+        let files = (0..5)
+            .map(|index| FileHandle::from(PathBuf::from(format!("{index}.txt"))))
+            .collect();
+
+        let mut loaded = Vec::new();
+        block_on(load_concurrently(
+            files,
+            limit,
+            |file| async move { file.file_name() },
+            |index, name| loaded.push((index, name)),
+        ));
+
+        loaded.sort();
+        loaded
+    }
+
+    #[test]
+    fn all_files_loaded() {
+        let expected: Vec<_> = (0..5)
+            .map(|index| (index, format!("{index}.txt")))
+            .collect();
+
+        assert_eq!(load_all(0), expected);
+        assert_eq!(load_all(1), expected);
+        assert_eq!(load_all(3), expected);
+        assert_eq!(load_all(10), expected);
+    }
+}
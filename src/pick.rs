@@ -4,13 +4,12 @@ use std::path::PathBuf;
 use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
 use bevy_tasks::prelude::*;
-use bevy_winit::{EventLoopProxy, EventLoopProxyWrapper};
 use crossbeam_channel::bounded;
-use rfd::AsyncFileDialog;
 
+use crate::kind::Dialog;
 use crate::{
-    handle_dialog_result, DialogResult, FileDialog, FileDialogPlugin, StreamReceiver, StreamSender,
-    WakeUpOnDrop,
+    handle_dialog_outcome, AppWaker, DialogOutcome, DialogPathsPicked, DialogResult, FileDialog,
+    FileDialogPlugin, FileMetadata, StreamReceiver, StreamSender, WakeUpOnDrop,
 };
 
 /// Event that gets sent when directory path gets selected from file system.
@@ -43,9 +42,23 @@ pub struct DialogFilePicked<T: PickFilePath> {
     /// Path of picked file.
     pub path: PathBuf,
 
+    /// Metadata of picked file, present only when
+    /// [`FileDialog::set_collect_metadata`] is enabled.
+    pub metadata: Option<FileMetadata>,
+
     marker: PhantomData<T>,
 }
 
+impl<T: PickFilePath> Clone for DialogFilePicked<T> {
+    fn clone(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+            metadata: self.metadata.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
 /// Event that gets sent when user closes pick file dialog without picking any file.
 #[derive(Message)]
 pub struct DialogFilePickCanceled<T: PickFilePath>(PhantomData<T>);
@@ -70,17 +83,7 @@ impl FileDialogPlugin {
     ///
     /// Does not exist in `WASM32`.
     pub fn with_pick_directory<T: PickDirectoryPath>(mut self) -> Self {
-        self.0.push(Box::new(|app| {
-            let (tx, rx) = bounded::<DialogResult<DialogDirectoryPicked<T>>>(1);
-            app.insert_resource(StreamSender(tx));
-            app.insert_resource(StreamReceiver(rx));
-            app.add_message::<DialogDirectoryPicked<T>>();
-            app.add_message::<DialogDirectoryPickCanceled<T>>();
-            app.add_systems(
-                First,
-                handle_dialog_result::<DialogDirectoryPicked<T>, DialogDirectoryPickCanceled<T>>,
-            );
-        }));
+        self.0.push(Box::new(register_pick_directory::<T>));
         self
     }
 
@@ -94,21 +97,87 @@ impl FileDialogPlugin {
     /// files, you need to use [`FileDialogPlugin::with_load_file`], which
     /// allows picking and loading in one step which is compatible with wasm.
     pub fn with_pick_file<T: PickFilePath>(mut self) -> Self {
-        self.0.push(Box::new(|app| {
-            let (tx, rx) = bounded::<DialogResult<DialogFilePicked<T>>>(1);
-            app.insert_resource(StreamSender(tx));
-            app.insert_resource(StreamReceiver(rx));
-            app.add_message::<DialogFilePicked<T>>();
-            app.add_message::<DialogFilePickCanceled<T>>();
-            app.add_systems(
-                First,
-                handle_dialog_result::<DialogFilePicked<T>, DialogFilePickCanceled<T>>,
-            );
-        }));
+        self.0.push(Box::new(register_pick_file::<T>));
         self
     }
 }
 
+/// Register everything needed for [`FileDialog::pick_directory_path`] with marker
+/// `T`, does nothing if it is already registered.
+pub(crate) fn register_pick_directory<T: PickDirectoryPath>(app: &mut App) {
+    if app
+        .world()
+        .contains_resource::<StreamSender<DialogResult<DialogDirectoryPicked<T>>>>()
+    {
+        return;
+    }
+
+    let (tx, rx) = bounded::<DialogResult<DialogDirectoryPicked<T>>>(1);
+    app.insert_resource(StreamSender(tx));
+    app.insert_resource(StreamReceiver(rx));
+    app.add_message::<DialogDirectoryPicked<T>>();
+    app.add_message::<DialogDirectoryPickCanceled<T>>();
+    app.add_systems(
+        First,
+        handle_dialog_outcome::<DialogDirectoryPicked<T>, DialogDirectoryPickCanceled<T>>,
+    );
+}
+
+/// Register everything needed for [`FileDialog::pick_file_path`] with marker
+/// `T`, does nothing if it is already registered.
+pub(crate) fn register_pick_file<T: PickFilePath>(app: &mut App) {
+    if app
+        .world()
+        .contains_resource::<StreamSender<DialogResult<DialogFilePicked<T>>>>()
+    {
+        return;
+    }
+
+    let (tx, rx) = bounded::<DialogResult<DialogFilePicked<T>>>(1);
+    app.insert_resource(StreamSender(tx));
+    app.insert_resource(StreamReceiver(rx));
+    app.add_message::<DialogFilePicked<T>>();
+    app.add_message::<DialogFilePickCanceled<T>>();
+    app.add_systems(
+        First,
+        handle_dialog_outcome::<DialogFilePicked<T>, DialogFilePickCanceled<T>>,
+    );
+}
+
+/// Open pick directory `dialog`, shared by [`FileDialog::pick_directory_path`],
+/// [`FileDialog::pick_directory_path_async`] and
+/// [`AsyncDialog::pick_directory_path`](crate::AsyncDialog::pick_directory_path).
+pub(crate) async fn pick_directory_outcome<T: PickDirectoryPath>(
+    dialog: Dialog,
+) -> DialogOutcome<T> {
+    let Some(file) = dialog.pick_folder().await else {
+        return DialogOutcome::Canceled;
+    };
+
+    DialogOutcome::DirectoryPicked(DialogDirectoryPicked {
+        path: file.path().to_path_buf(),
+        marker: PhantomData,
+    })
+}
+
+/// Open pick file `dialog`, shared by [`FileDialog::pick_file_path`],
+/// [`FileDialog::pick_file_path_async`] and
+/// [`AsyncDialog::pick_file_path`](crate::AsyncDialog::pick_file_path).
+pub(crate) async fn pick_file_outcome<T: PickFilePath>(
+    dialog: Dialog,
+    collect_metadata: bool,
+) -> DialogOutcome<T> {
+    let Some(file) = dialog.pick_file().await else {
+        return DialogOutcome::Canceled;
+    };
+
+    DialogOutcome::Picked(DialogFilePicked {
+        path: file.path().to_path_buf(),
+        metadata: FileMetadata::collect(collect_metadata, file.path(), None),
+        marker: PhantomData,
+    })
+}
+
 impl FileDialog<'_, '_, '_> {
     /// Open pick directory dialog and send [`DialogDirectoryPicked<T>`]
     /// event. You can read this event with Bevy's
@@ -116,33 +185,28 @@ impl FileDialog<'_, '_, '_> {
     ///
     /// Does not exist in `wasm32`.
     pub fn pick_directory_path<T: PickDirectoryPath>(self) {
-        self.commands.queue(|world: &mut World| {
+        self.commands.queue(move |world: &mut World| {
             let sender = world
                 .get_resource::<StreamSender<DialogResult<DialogDirectoryPicked<T>>>>()
                 .expect("FileDialogPlugin not initialized with 'with_pick_directory::<T>()'")
                 .0
                 .clone();
 
-            let event_loop_proxy = world
-                .get_resource::<EventLoopProxyWrapper>()
-                .map(|proxy| EventLoopProxy::clone(&**proxy));
+            let waker = AppWaker::from_world(world);
+
+            let dialog = self.config.apply::<T>(self.dialog, world);
 
             AsyncComputeTaskPool::get()
                 .spawn(async move {
-                    let file = self.dialog.pick_folder().await;
-                    let _wake_up = event_loop_proxy.as_ref().map(WakeUpOnDrop);
-
-                    let Some(file) = file else {
-                        sender.send(DialogResult::Canceled).unwrap();
-                        return;
-                    };
-
-                    let event = DialogDirectoryPicked {
-                        path: file.path().to_path_buf(),
-                        marker: PhantomData,
-                    };
-
-                    sender.send(DialogResult::Single(event)).unwrap();
+                    let outcome = pick_directory_outcome(dialog).await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
+
+                    match outcome {
+                        DialogOutcome::DirectoryPicked(event) => {
+                            sender.send(DialogResult::Single(event)).unwrap()
+                        }
+                        _ => sender.send(DialogResult::Canceled).unwrap(),
+                    }
                 })
                 .detach();
         });
@@ -155,21 +219,21 @@ impl FileDialog<'_, '_, '_> {
     ///
     /// Does not exist in `wasm32`.
     pub fn pick_multiple_directory_paths<T: PickDirectoryPath>(self) {
-        self.commands.queue(|world: &mut World| {
+        self.commands.queue(move |world: &mut World| {
             let sender = world
                 .get_resource::<StreamSender<DialogResult<DialogDirectoryPicked<T>>>>()
                 .expect("FileDialogPlugin not initialized with 'with_pick_directory::<T>()'")
                 .0
                 .clone();
 
-            let event_loop_proxy = world
-                .get_resource::<EventLoopProxyWrapper>()
-                .map(|proxy| EventLoopProxy::clone(&**proxy));
+            let waker = AppWaker::from_world(world);
+
+            let dialog = self.config.apply::<T>(self.dialog, world);
 
             AsyncComputeTaskPool::get()
                 .spawn(async move {
-                    let files = AsyncFileDialog::new().pick_folders().await;
-                    let _wake_up = event_loop_proxy.as_ref().map(WakeUpOnDrop);
+                    let files = dialog.pick_folders().await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
 
                     let Some(files) = files else {
                         sender.send(DialogResult::Canceled).unwrap();
@@ -198,33 +262,28 @@ impl FileDialog<'_, '_, '_> {
     /// need to use [`FileDialog::load_file`], which does picking and loading in
     /// one step which is compatible with wasm.
     pub fn pick_file_path<T: PickFilePath>(self) {
-        self.commands.queue(|world: &mut World| {
+        self.commands.queue(move |world: &mut World| {
             let sender = world
                 .get_resource::<StreamSender<DialogResult<DialogFilePicked<T>>>>()
                 .expect("FileDialogPlugin not initialized with 'with_pick_file::<T>()'")
                 .0
                 .clone();
 
-            let event_loop_proxy = world
-                .get_resource::<EventLoopProxyWrapper>()
-                .map(|proxy| EventLoopProxy::clone(&**proxy));
+            let waker = AppWaker::from_world(world);
+
+            let dialog = self.config.apply::<T>(self.dialog, world);
 
             AsyncComputeTaskPool::get()
                 .spawn(async move {
-                    let file = self.dialog.pick_file().await;
-                    let _wake_up = event_loop_proxy.as_ref().map(WakeUpOnDrop);
-
-                    let Some(file) = file else {
-                        sender.send(DialogResult::Canceled).unwrap();
-                        return;
-                    };
-
-                    let event = DialogFilePicked {
-                        path: file.path().to_path_buf(),
-                        marker: PhantomData,
-                    };
-
-                    sender.send(DialogResult::Single(event)).unwrap();
+                    let outcome = pick_file_outcome(dialog, self.collect_metadata).await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
+
+                    match outcome {
+                        DialogOutcome::Picked(event) => {
+                            sender.send(DialogResult::Single(event)).unwrap()
+                        }
+                        _ => sender.send(DialogResult::Canceled).unwrap(),
+                    }
                 })
                 .detach();
         });
@@ -235,25 +294,32 @@ impl FileDialog<'_, '_, '_> {
     /// can get each path by reading every event received with with Bevy's
     /// [`EventReader<DialogFilePicked<T>>`].
     ///
+    /// When [`FileDialogPlugin::with_batch_messages::<T>`] is used, single
+    /// [`DialogPathsPicked<T>`] with all paths gets sent as well.
+    ///
     /// Does not exist in `wasm32`. If you want cross-platform solution, you
     /// need to use [`FileDialog::load_multiple_files`], which does picking and
     /// loading in one step which is compatible with wasm.
     pub fn pick_multiple_file_paths<T: PickDirectoryPath>(self) {
-        self.commands.queue(|world: &mut World| {
+        self.commands.queue(move |world: &mut World| {
             let sender = world
                 .get_resource::<StreamSender<DialogResult<DialogFilePicked<T>>>>()
                 .expect("FileDialogPlugin not initialized with 'with_pick_file::<T>()'")
                 .0
                 .clone();
 
-            let event_loop_proxy = world
-                .get_resource::<EventLoopProxyWrapper>()
-                .map(|proxy| EventLoopProxy::clone(&**proxy));
+            let waker = AppWaker::from_world(world);
+
+            let batch_sender = world
+                .get_resource::<StreamSender<DialogPathsPicked<T>>>()
+                .map(|sender| sender.0.clone());
+
+            let dialog = self.config.apply::<T>(self.dialog, world);
 
             AsyncComputeTaskPool::get()
                 .spawn(async move {
-                    let files = AsyncFileDialog::new().pick_files().await;
-                    let _wake_up = event_loop_proxy.as_ref().map(WakeUpOnDrop);
+                    let files = dialog.pick_files().await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
 
                     let Some(files) = files else {
                         sender.send(DialogResult::Canceled).unwrap();
@@ -264,9 +330,20 @@ impl FileDialog<'_, '_, '_> {
                         .into_iter()
                         .map(|file| DialogFilePicked {
                             path: file.path().to_path_buf(),
+                            metadata: FileMetadata::collect(
+                                self.collect_metadata,
+                                file.path(),
+                                None,
+                            ),
                             marker: PhantomData,
                         })
-                        .collect();
+                        .collect::<Vec<_>>();
+
+                    if let Some(batch_sender) = batch_sender {
+                        batch_sender
+                            .send(DialogPathsPicked::new(events.clone()))
+                            .unwrap();
+                    }
 
                     sender.send(DialogResult::Batch(events)).unwrap();
                 })
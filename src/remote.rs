@@ -0,0 +1,495 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_remote::{error_codes, BrpError, BrpResult, RemoteMethodSystemId, RemoteMethods};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::dynamic::register_dynamic_dialogs;
+use crate::{
+    ContentType, DialogKindId, DynamicDialogOutcome, DynamicDialogResult, FileDialog,
+    FileDialogExt, FileDialogPlugin,
+};
+
+/// The method path for a `file_dialog/pick_file` request, opens pick file
+/// dialog and returns `{ "id": <id> }` of the dialog.
+pub const BRP_FILE_DIALOG_PICK_FILE_METHOD: &str = "file_dialog/pick_file";
+
+/// The method path for a `file_dialog/load` request, opens pick file dialog,
+/// loads the picked file and returns `{ "id": <id> }` of the dialog.
+pub const BRP_FILE_DIALOG_LOAD_METHOD: &str = "file_dialog/load";
+
+/// The method path for a `file_dialog/save` request, opens save file dialog,
+/// saves `contents` or `text` to the picked file and returns
+/// `{ "id": <id> }` of the dialog.
+pub const BRP_FILE_DIALOG_SAVE_METHOD: &str = "file_dialog/save";
+
+/// The method path for a `file_dialog/result` request, returns result of
+/// dialog with `id`, or `{ "status": "pending" }` when the dialog is still
+/// open.
+pub const BRP_FILE_DIALOG_RESULT_METHOD: &str = "file_dialog/result";
+
+/// The method path for a `file_dialog/result+watch` request, streams result
+/// of dialog with `id` once the dialog completes.
+pub const BRP_FILE_DIALOG_RESULT_AND_WATCH_METHOD: &str = "file_dialog/result+watch";
+
+/// Dynamic dialog kinds of dialogs opened remotely are this prefix followed
+/// by id of the dialog.
+const REMOTE_KIND_PREFIX: &str = "file_dialog/remote/";
+
+/// How many results that were not fetched yet are kept, results of the oldest
+/// dialogs get dropped first, so that clients that never fetch the results
+/// don't grow the memory without limit.
+const MAX_FINISHED_RESULTS: usize = 64;
+
+/// Options of the dialog, same as [`FileDialog`] builder functions.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct DialogParams {
+    title: Option<String>,
+    directory: Option<PathBuf>,
+    file_name: Option<String>,
+    filters: Vec<FilterParams>,
+    max_file_size: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct FilterParams {
+    name: String,
+    extensions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SaveParams {
+    #[serde(flatten)]
+    dialog: DialogParams,
+    contents: Option<Vec<u8>>,
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ResultParams {
+    id: u64,
+}
+
+impl DialogParams {
+    fn apply<'w, 's, 'a>(self, mut dialog: FileDialog<'w, 's, 'a>) -> FileDialog<'w, 's, 'a> {
+        if let Some(title) = self.title {
+            dialog = dialog.set_title(title);
+        }
+
+        if let Some(directory) = self.directory {
+            dialog = dialog.set_directory(directory);
+        }
+
+        if let Some(file_name) = self.file_name {
+            dialog = dialog.set_file_name(file_name);
+        }
+
+        for filter in self.filters {
+            dialog = dialog.add_filter(filter.name, &filter.extensions);
+        }
+
+        if let Some(max_size) = self.max_file_size {
+            dialog = dialog.set_max_file_size(max_size);
+        }
+
+        dialog
+    }
+}
+
+/// Dialogs opened remotely and their results that were not fetched yet.
+#[derive(Resource, Default)]
+struct RemoteDialogs {
+    next_id: u64,
+    pending: HashSet<u64>,
+    finished: BTreeMap<u64, Value>,
+}
+
+impl RemoteDialogs {
+    fn open(&mut self) -> (u64, DialogKindId) {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.pending.insert(id);
+        (id, DialogKindId::new(format!("{REMOTE_KIND_PREFIX}{id}")))
+    }
+
+    /// Take result of dialog with `id`, `None` if it is still open or the
+    /// result was already taken.
+    fn take(&mut self, id: u64) -> BrpResult<Option<Value>> {
+        if id >= self.next_id {
+            return Err(BrpError {
+                code: error_codes::INVALID_PARAMS,
+                message: format!("File dialog with id {id} does not exist"),
+                data: None,
+            });
+        }
+
+        Ok(self.finished.remove(&id))
+    }
+
+    /// Store `result` of dialog with `id` until it gets taken, dropping the
+    /// oldest results over [`MAX_FINISHED_RESULTS`].
+    fn finish(&mut self, id: u64, result: Value) {
+        self.pending.remove(&id);
+        self.finished.insert(id, result);
+
+        while self.finished.len() > MAX_FINISHED_RESULTS {
+            self.finished.pop_first();
+        }
+    }
+}
+
+fn parse<T: DeserializeOwned>(params: Option<Value>) -> BrpResult<T> {
+    serde_json::from_value(params.unwrap_or_else(|| json!({}))).map_err(|err| BrpError {
+        code: error_codes::INVALID_PARAMS,
+        message: err.to_string(),
+        data: None,
+    })
+}
+
+fn process_remote_pick_file_request(
+    In(params): In<Option<Value>>,
+    mut commands: Commands,
+    mut dialogs: ResMut<RemoteDialogs>,
+) -> BrpResult {
+    let params: DialogParams = parse(params)?;
+    let (id, kind) = dialogs.open();
+    params.apply(commands.dialog()).pick_file_path_dynamic(kind);
+    Ok(json!({ "id": id }))
+}
+
+fn process_remote_load_request(
+    In(params): In<Option<Value>>,
+    mut commands: Commands,
+    mut dialogs: ResMut<RemoteDialogs>,
+) -> BrpResult {
+    let params: DialogParams = parse(params)?;
+    let (id, kind) = dialogs.open();
+    params.apply(commands.dialog()).load_file_dynamic(kind);
+    Ok(json!({ "id": id }))
+}
+
+fn process_remote_save_request(
+    In(params): In<Option<Value>>,
+    mut commands: Commands,
+    mut dialogs: ResMut<RemoteDialogs>,
+) -> BrpResult {
+    let params: SaveParams = parse(params)?;
+    let contents = match (params.contents, params.text) {
+        (Some(contents), None) => contents,
+        (None, Some(text)) => text.into_bytes(),
+        _ => {
+            return Err(BrpError {
+                code: error_codes::INVALID_PARAMS,
+                message: "Expected exactly one of `contents` or `text`".to_string(),
+                data: None,
+            })
+        }
+    };
+
+    let (id, kind) = dialogs.open();
+    params
+        .dialog
+        .apply(commands.dialog())
+        .save_file_dynamic(kind, contents);
+    Ok(json!({ "id": id }))
+}
+
+fn process_remote_result_request(
+    In(params): In<Option<Value>>,
+    mut dialogs: ResMut<RemoteDialogs>,
+) -> BrpResult {
+    let ResultParams { id } = parse(params)?;
+    match dialogs.take(id)? {
+        Some(result) => Ok(result),
+        None if dialogs.pending.contains(&id) => Ok(json!({ "status": "pending" })),
+        None => Err(BrpError {
+            code: error_codes::INVALID_PARAMS,
+            message: format!("Result of file dialog with id {id} was already taken or dropped"),
+            data: None,
+        }),
+    }
+}
+
+fn process_remote_result_watching_request(
+    In(params): In<Option<Value>>,
+    mut dialogs: ResMut<RemoteDialogs>,
+) -> BrpResult<Option<Value>> {
+    let ResultParams { id } = parse(params)?;
+    dialogs.take(id)
+}
+
+fn collect_remote_results(
+    mut ev_result: MessageReader<DynamicDialogResult>,
+    mut dialogs: ResMut<RemoteDialogs>,
+) {
+    for ev in ev_result.read() {
+        let Some(id) = ev
+            .kind
+            .as_str()
+            .strip_prefix(REMOTE_KIND_PREFIX)
+            .and_then(|id| id.parse().ok())
+        else {
+            continue;
+        };
+
+        dialogs.finish(id, outcome_to_json(&ev.outcome));
+    }
+}
+
+fn outcome_to_json(outcome: &DynamicDialogOutcome) -> Value {
+    match outcome {
+        DynamicDialogOutcome::Loaded {
+            file_name,
+            contents,
+            content_type,
+            path,
+            ..
+        } => json!({
+            "status": "loaded",
+            "file_name": file_name,
+            "path": path,
+            "contents": contents,
+            "content_type": content_type.map(ContentType::mime_type),
+        }),
+        DynamicDialogOutcome::Saved {
+            file_name,
+            result,
+            path,
+        } => json!({
+            "status": "saved",
+            "file_name": file_name,
+            "path": path,
+            "error": result.as_ref().err().map(ToString::to_string),
+        }),
+        DynamicDialogOutcome::Picked { path } => json!({
+            "status": "picked",
+            "path": path,
+        }),
+        DynamicDialogOutcome::TooLarge {
+            file_name,
+            size,
+            max_size,
+        } => json!({
+            "status": "too_large",
+            "file_name": file_name,
+            "size": size,
+            "max_size": max_size,
+        }),
+        DynamicDialogOutcome::LoadFailed { file_name, error } => json!({
+            "status": "load_failed",
+            "file_name": file_name,
+            "error": error.to_string(),
+        }),
+        DynamicDialogOutcome::Canceled => json!({ "status": "canceled" }),
+    }
+}
+
+fn register_remote_methods(world: &mut World) {
+    let methods = [
+        (
+            BRP_FILE_DIALOG_PICK_FILE_METHOD,
+            RemoteMethodSystemId::Instant(world.register_system(process_remote_pick_file_request)),
+        ),
+        (
+            BRP_FILE_DIALOG_LOAD_METHOD,
+            RemoteMethodSystemId::Instant(world.register_system(process_remote_load_request)),
+        ),
+        (
+            BRP_FILE_DIALOG_SAVE_METHOD,
+            RemoteMethodSystemId::Instant(world.register_system(process_remote_save_request)),
+        ),
+        (
+            BRP_FILE_DIALOG_RESULT_METHOD,
+            RemoteMethodSystemId::Instant(world.register_system(process_remote_result_request)),
+        ),
+        (
+            BRP_FILE_DIALOG_RESULT_AND_WATCH_METHOD,
+            RemoteMethodSystemId::Watching(
+                world.register_system(process_remote_result_watching_request),
+            ),
+        ),
+    ];
+
+    let mut remote_methods = world
+        .get_resource_mut::<RemoteMethods>()
+        .expect("FileDialogPlugin::with_remote_methods requires RemotePlugin");
+
+    for (name, system_id) in methods {
+        remote_methods.insert(name, system_id);
+    }
+}
+
+pub(crate) fn register_remote(app: &mut App) {
+    if app.world().contains_resource::<RemoteDialogs>() {
+        return;
+    }
+
+    register_dynamic_dialogs(app);
+    app.init_resource::<RemoteDialogs>();
+    app.add_systems(PreStartup, register_remote_methods);
+    app.add_systems(PreUpdate, collect_remote_results);
+}
+
+impl FileDialogPlugin {
+    /// Register Bevy Remote Protocol methods that allow opening dialogs from
+    /// remote clients. Requires `RemotePlugin` from `bevy_remote`.
+    ///
+    /// Methods [`BRP_FILE_DIALOG_PICK_FILE_METHOD`],
+    /// [`BRP_FILE_DIALOG_LOAD_METHOD`] and [`BRP_FILE_DIALOG_SAVE_METHOD`] open
+    /// the dialog and return its id. They take the same options as
+    /// [`FileDialog`], `title`, `directory`, `file_name`, `filters` as list of
+    /// `{ "name": ..., "extensions": [...] }` and `max_file_size`. Save also
+    /// requires either `contents` as array of bytes or `text`.
+    ///
+    /// The result can be fetched with [`BRP_FILE_DIALOG_RESULT_METHOD`] or
+    /// streamed with [`BRP_FILE_DIALOG_RESULT_AND_WATCH_METHOD`], both take
+    /// `{ "id": <id> }`. Each result has `status` field, which is one of
+    /// `picked`, `loaded`, `saved`, `too_large`, `load_failed`, `canceled` or
+    /// `pending`. Result is removed once it is fetched and only results of
+    /// the latest 64 dialogs that were not fetched yet are kept.
+    ///
+    /// Dialogs can be answered without showing them with
+    /// [`MockDialogBackend`](crate::MockDialogBackend), for testing clients.
+    ///
+    /// Requires `remote` feature, does not exist in `wasm32`.
+    pub fn with_remote_methods(mut self) -> Self {
+        self.0.push(Box::new(register_remote));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::Duration;
+
+    use bevy_remote::{BrpMessage, BrpSender, RemotePlugin};
+    use bevy_tasks::{AsyncComputeTaskPool, TaskPool};
+
+    use super::*;
+    use crate::MockDialogBackend;
+
+    fn app() -> App {
+        AsyncComputeTaskPool::get_or_init(TaskPool::new);
+
+        let mut app = App::new();
+        app.add_plugins((
+            RemotePlugin::default(),
+            FileDialogPlugin::new().with_remote_methods(),
+        ));
+        app.init_resource::<MockDialogBackend>();
+        app.update();
+        app
+    }
+
+    fn request(app: &mut App, method: &str, params: Value) -> BrpResult {
+        let (sender, receiver) = async_channel::bounded(1);
+        app.world()
+            .resource::<BrpSender>()
+            .try_send(BrpMessage {
+                method: method.to_string(),
+                params: Some(params),
+                sender,
+            })
+            .unwrap();
+        app.update();
+        receiver.try_recv().unwrap()
+    }
+
+    fn open(app: &mut App, method: &str, params: Value) -> Value {
+        request(app, method, params).unwrap()["id"].clone()
+    }
+
+    /// Fetch result of dialog with `id` once it is not pending anymore.
+    fn wait_for_result(app: &mut App, id: &Value) -> Value {
+        for _ in 0..100 {
+            let result = request(app, BRP_FILE_DIALOG_RESULT_METHOD, json!({ "id": id })).unwrap();
+            if result["status"] != "pending" {
+                return result;
+            }
+
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        panic!("file dialog with id {id} did not finish");
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join("bevy_file_dialog_remote_save.txt");
+        let mut app = app();
+        let mut backend = app.world_mut().resource_mut::<MockDialogBackend>();
+        backend.push_pick(&path);
+        backend.push_pick(&path);
+
+        let id = open(
+            &mut app,
+            BRP_FILE_DIALOG_SAVE_METHOD,
+            json!({ "text": "hello", "title": "Save" }),
+        );
+        let saved = wait_for_result(&mut app, &id);
+        assert_eq!(saved["status"], "saved");
+        assert_eq!(saved["error"], Value::Null);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "hello");
+
+        let id = open(&mut app, BRP_FILE_DIALOG_LOAD_METHOD, json!({}));
+        let loaded = wait_for_result(&mut app, &id);
+        assert_eq!(loaded["status"], "loaded");
+        assert_eq!(loaded["contents"], json!(b"hello"));
+    }
+
+    #[test]
+    fn pick_and_cancel() {
+        let mut app = app();
+        let mut backend = app.world_mut().resource_mut::<MockDialogBackend>();
+        backend.push_pick("picked.txt");
+        backend.push_cancel();
+
+        let picked = open(&mut app, BRP_FILE_DIALOG_PICK_FILE_METHOD, json!({}));
+        let canceled = open(&mut app, BRP_FILE_DIALOG_PICK_FILE_METHOD, json!({}));
+        assert_eq!(
+            wait_for_result(&mut app, &picked),
+            json!({ "status": "picked", "path": "picked.txt" })
+        );
+        assert_eq!(
+            wait_for_result(&mut app, &canceled),
+            json!({ "status": "canceled" })
+        );
+
+        let taken = request(
+            &mut app,
+            BRP_FILE_DIALOG_RESULT_METHOD,
+            json!({ "id": picked }),
+        );
+        assert!(taken.is_err());
+    }
+
+    #[test]
+    fn invalid_params() {
+        let mut app = app();
+        let save = request(&mut app, BRP_FILE_DIALOG_SAVE_METHOD, json!({}));
+        assert_eq!(save.unwrap_err().code, error_codes::INVALID_PARAMS);
+
+        let result = request(&mut app, BRP_FILE_DIALOG_RESULT_METHOD, json!({ "id": 0 }));
+        assert_eq!(result.unwrap_err().code, error_codes::INVALID_PARAMS);
+    }
+
+    #[test]
+    fn finished_results_are_capped() {
+        let mut dialogs = RemoteDialogs::default();
+        for _ in 0..MAX_FINISHED_RESULTS + 1 {
+            let (id, _) = dialogs.open();
+            dialogs.finish(id, json!({ "status": "canceled" }));
+        }
+
+        assert_eq!(dialogs.finished.len(), MAX_FINISHED_RESULTS);
+        assert_eq!(dialogs.take(0).unwrap(), None);
+        assert!(dialogs.take(1).unwrap().is_some());
+        assert_eq!(dialogs.take(1).unwrap(), None);
+    }
+}
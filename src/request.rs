@@ -0,0 +1,412 @@
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use crossbeam_channel::bounded;
+
+use crate::{
+    DialogOutcome, FileDialog, FileDialogExt, FileDialogPlugin, LoadError, StreamReceiver,
+    StreamSender,
+};
+
+/// Component that opens dialog when it gets inserted on an entity. When the
+/// dialog completes, [`FileDialogResponse`] gets inserted on the same entity.
+///
+/// The dialog opens only when the request gets added, changing the request
+/// in place does not open it again. To open the dialog again, remove the
+/// request and insert it again, previous [`FileDialogResponse`] gets removed
+/// when the dialog opens. When the entity gets despawned before the dialog
+/// completes, the response is dropped.
+///
+/// ```rust
+/// fn open(mut commands: Commands) {
+///     commands.spawn(FileDialogRequest {
+///         title: Some("Open Level".to_string()),
+///         filters: vec![("Level".to_string(), vec!["ron".to_string()])],
+///         ..FileDialogRequest::load()
+///     });
+/// }
+///
+/// fn opened(responses: Query<&FileDialogResponse, Added<FileDialogResponse>>) {
+///     for response in &responses {
+///         if let FileDialogResponse::Loaded { file_name, .. } = response {
+///             eprintln!("Loaded {file_name}");
+///         }
+///     }
+/// }
+/// ```
+///
+/// Requires [`FileDialogPlugin::with_requests`].
+#[derive(Component, Clone, Debug, Default)]
+pub struct FileDialogRequest {
+    /// What the dialog does.
+    pub kind: FileDialogRequestKind,
+
+    /// Title of the dialog, same as [`FileDialog::set_title`].
+    pub title: Option<String>,
+
+    /// Starting directory of the dialog, same as
+    /// [`FileDialog::set_directory`].
+    pub directory: Option<PathBuf>,
+
+    /// Starting file name of the dialog, same as
+    /// [`FileDialog::set_file_name`].
+    pub file_name: Option<String>,
+
+    /// Named extension filters of the dialog, same as
+    /// [`FileDialog::add_filter`].
+    pub filters: Vec<(String, Vec<String>)>,
+
+    /// Size limit of loaded file, same as [`FileDialog::set_max_file_size`].
+    pub max_file_size: Option<u64>,
+}
+
+/// What the dialog opened with [`FileDialogRequest`] does.
+#[derive(Clone, Debug, Default)]
+pub enum FileDialogRequestKind {
+    /// Pick file and load its contents, same as [`FileDialog::load_file`].
+    #[default]
+    Load,
+
+    /// Pick file and save the contents to it, same as
+    /// [`FileDialog::save_file`].
+    Save(Vec<u8>),
+
+    /// Pick file path, same as [`FileDialog::pick_file_path`].
+    ///
+    /// Does not exist in wasm, you can use this on native platforms only.
+    #[cfg(not(target_arch = "wasm32"))]
+    PickFile,
+
+    /// Pick directory path, same as [`FileDialog::pick_directory_path`].
+    ///
+    /// Does not exist in wasm, you can use this on native platforms only.
+    #[cfg(not(target_arch = "wasm32"))]
+    PickDirectory,
+}
+
+impl FileDialogRequest {
+    /// Request to pick file and load its contents.
+    pub fn load() -> Self {
+        Self::default()
+    }
+
+    /// Request to pick file and save `contents` to it.
+    pub fn save(contents: Vec<u8>) -> Self {
+        Self {
+            kind: FileDialogRequestKind::Save(contents),
+            ..Default::default()
+        }
+    }
+
+    /// Request to pick file path.
+    ///
+    /// Does not exist in `wasm32`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn pick_file() -> Self {
+        Self {
+            kind: FileDialogRequestKind::PickFile,
+            ..Default::default()
+        }
+    }
+
+    /// Request to pick directory path.
+    ///
+    /// Does not exist in `wasm32`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn pick_directory() -> Self {
+        Self {
+            kind: FileDialogRequestKind::PickDirectory,
+            ..Default::default()
+        }
+    }
+
+    fn apply<'w, 's, 'a>(&self, mut dialog: FileDialog<'w, 's, 'a>) -> FileDialog<'w, 's, 'a> {
+        if let Some(title) = &self.title {
+            dialog = dialog.set_title(title);
+        }
+
+        if let Some(directory) = &self.directory {
+            dialog = dialog.set_directory(directory);
+        }
+
+        if let Some(file_name) = &self.file_name {
+            dialog = dialog.set_file_name(file_name);
+        }
+
+        for (name, extensions) in &self.filters {
+            dialog = dialog.add_filter(name, extensions);
+        }
+
+        if let Some(max_size) = self.max_file_size {
+            dialog = dialog.set_max_file_size(max_size);
+        }
+
+        dialog
+    }
+}
+
+/// Component that gets inserted on entity with [`FileDialogRequest`] when its
+/// dialog completes.
+#[derive(Component, Debug)]
+pub enum FileDialogResponse {
+    /// File contents were loaded.
+    Loaded {
+        /// Name of loaded file.
+        file_name: String,
+
+        /// Byte contents of loaded file.
+        contents: Vec<u8>,
+
+        /// Path to loaded file.
+        ///
+        /// Does not exist in wasm, you can use this on native platforms only.
+        #[cfg(not(target_arch = "wasm32"))]
+        path: PathBuf,
+    },
+
+    /// File contents were saved.
+    Saved {
+        /// Name of saved file.
+        file_name: String,
+
+        /// Result of save file system operation.
+        result: io::Result<()>,
+
+        /// Path to saved file.
+        ///
+        /// Does not exist in wasm, you can use this on native platforms only.
+        #[cfg(not(target_arch = "wasm32"))]
+        path: PathBuf,
+    },
+
+    /// File or directory path was picked.
+    ///
+    /// Does not exist in wasm, you can use this on native platforms only.
+    #[cfg(not(target_arch = "wasm32"))]
+    Picked {
+        /// Picked path.
+        path: PathBuf,
+    },
+
+    /// Picked file was larger than [`FileDialogRequest::max_file_size`].
+    TooLarge {
+        /// Name of picked file.
+        file_name: String,
+
+        /// Actual size of picked file in bytes.
+        size: u64,
+
+        /// Maximum allowed size in bytes.
+        max_size: u64,
+    },
+
+    /// Picked file could not be read or decoded.
+    LoadFailed {
+        /// Name of picked file.
+        file_name: String,
+
+        /// Reason why the file could not be loaded.
+        error: LoadError,
+    },
+
+    /// User closed the dialog without picking any file or directory.
+    Canceled,
+}
+
+/// Marker of dialogs opened with [`FileDialogRequest`].
+struct RequestedDialog;
+
+impl From<DialogOutcome<RequestedDialog>> for FileDialogResponse {
+    fn from(outcome: DialogOutcome<RequestedDialog>) -> Self {
+        match outcome {
+            DialogOutcome::Loaded(event) => Self::Loaded {
+                file_name: event.file_name,
+                contents: Arc::unwrap_or_clone(event.contents),
+                #[cfg(not(target_arch = "wasm32"))]
+                path: event.path,
+            },
+            DialogOutcome::Saved(event) => Self::Saved {
+                file_name: event.file_name,
+                result: event.result,
+                #[cfg(not(target_arch = "wasm32"))]
+                path: event.path,
+            },
+            #[cfg(not(target_arch = "wasm32"))]
+            DialogOutcome::Picked(event) => Self::Picked { path: event.path },
+            #[cfg(not(target_arch = "wasm32"))]
+            DialogOutcome::DirectoryPicked(event) => Self::Picked { path: event.path },
+            DialogOutcome::TooLarge(event) => Self::TooLarge {
+                file_name: event.file_name,
+                size: event.size,
+                max_size: event.max_size,
+            },
+            DialogOutcome::Failed(event) => Self::LoadFailed {
+                file_name: event.file_name,
+                error: event.error,
+            },
+            DialogOutcome::Canceled => Self::Canceled,
+        }
+    }
+}
+
+/// Response of dialog opened by request on the entity.
+struct RequestResponse(Entity, FileDialogResponse);
+
+pub(crate) fn register_requests(app: &mut App) {
+    if app
+        .world()
+        .contains_resource::<StreamSender<RequestResponse>>()
+    {
+        return;
+    }
+
+    let (tx, rx) = bounded::<RequestResponse>(1);
+    app.insert_resource(StreamSender(tx));
+    app.insert_resource(StreamReceiver(rx));
+    app.add_systems(First, insert_responses);
+    app.add_systems(PostUpdate, open_requested_dialogs);
+}
+
+fn open_requested_dialogs(
+    mut commands: Commands,
+    requests: Query<(Entity, &FileDialogRequest), Added<FileDialogRequest>>,
+) {
+    for (entity, request) in &requests {
+        // So that the response of this dialog is added again
+        commands.entity(entity).remove::<FileDialogResponse>();
+
+        let deliver = move |world: &mut World| {
+            let sender = world
+                .get_resource::<StreamSender<RequestResponse>>()
+                .expect("FileDialogPlugin not initialized with 'with_requests()'")
+                .0
+                .clone();
+
+            move |outcome: DialogOutcome<RequestedDialog>| {
+                sender
+                    .send(RequestResponse(entity, outcome.into()))
+                    .unwrap();
+            }
+        };
+
+        let dialog = request.apply(commands.dialog());
+
+        match &request.kind {
+            FileDialogRequestKind::Load => dialog.open_load_file(deliver),
+            FileDialogRequestKind::Save(contents) => {
+                dialog.open_save_file(contents.clone(), deliver)
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            FileDialogRequestKind::PickFile => dialog.open_pick_file(deliver),
+            #[cfg(not(target_arch = "wasm32"))]
+            FileDialogRequestKind::PickDirectory => dialog.open_pick_directory(deliver),
+        }
+    }
+}
+
+fn insert_responses(receiver: Res<StreamReceiver<RequestResponse>>, mut commands: Commands) {
+    for RequestResponse(entity, response) in receiver.try_iter() {
+        // The entity may have been despawned, nobody is interested in the response then
+        commands.entity(entity).try_insert(response);
+    }
+}
+
+impl FileDialogPlugin {
+    /// Allow opening dialogs by inserting [`FileDialogRequest`] on entities,
+    /// [`FileDialogResponse`] gets inserted on the same entity when the
+    /// dialog completes.
+    pub fn with_requests(mut self) -> Self {
+        self.0.push(Box::new(register_requests));
+        self
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::fs;
+    use std::time::Duration;
+
+    use bevy_tasks::{AsyncComputeTaskPool, TaskPool};
+
+    use super::*;
+    use crate::MockDialogBackend;
+
+    fn app() -> App {
+        AsyncComputeTaskPool::get_or_init(TaskPool::new);
+
+        let mut app = App::new();
+        app.add_plugins(FileDialogPlugin::new().with_requests());
+        app.init_resource::<MockDialogBackend>();
+        app
+    }
+
+    /// Update `app` until response gets inserted on `entity`.
+    fn wait_for_response(app: &mut App, entity: Entity) -> &FileDialogResponse {
+        for _ in 0..100 {
+            app.update();
+            if app.world().get::<FileDialogResponse>(entity).is_some() {
+                return app.world().get::<FileDialogResponse>(entity).unwrap();
+            }
+
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        panic!("no response inserted on {entity}");
+    }
+
+    #[test]
+    fn opens_only_when_added() {
+        let mut app = app();
+        let mut backend = app.world_mut().resource_mut::<MockDialogBackend>();
+        backend.push_pick("first.txt");
+        backend.push_pick("second.txt");
+
+        let entity = app.world_mut().spawn(FileDialogRequest::pick_file()).id();
+        let response = wait_for_response(&mut app, entity);
+        assert!(
+            matches!(response, FileDialogResponse::Picked { path } if path.ends_with("first.txt"))
+        );
+
+        app.world_mut()
+            .get_mut::<FileDialogRequest>(entity)
+            .unwrap()
+            .title = Some("Changed".to_string());
+        app.update();
+        app.update();
+
+        let backend = app.world().resource::<MockDialogBackend>();
+        assert_eq!(backend.next(), [PathBuf::from("second.txt")]);
+    }
+
+    #[test]
+    fn too_large() {
+        let path = std::env::temp_dir().join("bevy_file_dialog_request_too_large.txt");
+        fs::write(&path, b"0123456789").unwrap();
+
+        let mut app = app();
+        app.world_mut()
+            .resource_mut::<MockDialogBackend>()
+            .push_pick(&path);
+
+        let entity = app
+            .world_mut()
+            .spawn(FileDialogRequest {
+                max_file_size: Some(4),
+                ..FileDialogRequest::load()
+            })
+            .id();
+
+        let response = wait_for_response(&mut app, entity);
+        assert!(matches!(
+            response,
+            FileDialogResponse::TooLarge {
+                size: 10,
+                max_size: 4,
+                ..
+            }
+        ));
+    }
+}
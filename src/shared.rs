@@ -0,0 +1,330 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::marker::PhantomData;
+use std::ops::{Bound, Deref, Range, RangeBounds};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_tasks::prelude::*;
+use crossbeam_channel::bounded;
+use rfd::FileHandle;
+
+use crate::{
+    handle_dialog_result, AppWaker, DialogFileLoadCanceled, DialogFileLoadFailed,
+    DialogFileTooLarge, DialogResult, FileDialog, LoadContents, LoadError, LoadOptions,
+    MaxFileSize, StreamReceiver, StreamSender, WakeUpOnDrop,
+};
+
+/// Cheaply clonable buffer with contents of loaded file. Dereferences to
+/// `[u8]`.
+///
+/// Depending on options used when loading, the buffer is either read into
+/// memory or memory-mapped.
+#[derive(Clone)]
+pub struct FileBuffer(Arc<BufferInner>);
+
+enum BufferInner {
+    Owned(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+impl FileBuffer {
+    /// Returns `true` if the buffer is memory-mapped file instead of a copy
+    /// in memory.
+    pub fn is_mapped(&self) -> bool {
+        match *self.0 {
+            BufferInner::Owned(_) => false,
+            #[cfg(feature = "mmap")]
+            BufferInner::Mapped(_) => true,
+        }
+    }
+}
+
+impl Deref for FileBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match *self.0 {
+            BufferInner::Owned(ref bytes) => bytes,
+            #[cfg(feature = "mmap")]
+            BufferInner::Mapped(ref map) => map,
+        }
+    }
+}
+
+impl AsRef<[u8]> for FileBuffer {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl From<Vec<u8>> for FileBuffer {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(Arc::new(BufferInner::Owned(bytes)))
+    }
+}
+
+/// Event that gets sent when file contents get loaded from file system with
+/// [`FileDialog::load_file_shared`].
+#[derive(Message)]
+pub struct DialogFileLoadedShared<T: LoadContents> {
+    /// Name of loaded file.
+    pub file_name: String,
+
+    /// Shared contents of loaded file, limited to the range set with
+    /// [`FileDialog::set_read_range`].
+    pub contents: FileBuffer,
+
+    /// Path to loaded file.
+    pub path: PathBuf,
+
+    marker: PhantomData<T>,
+}
+
+pub(crate) fn register_load_shared<T: LoadContents>(app: &mut App) {
+    let (tx, rx) = bounded::<DialogResult<DialogFileLoadedShared<T>>>(1);
+    app.insert_resource(StreamSender(tx));
+    app.insert_resource(StreamReceiver(rx));
+    app.add_message::<DialogFileLoadedShared<T>>();
+    app.add_systems(
+        First,
+        handle_dialog_result::<DialogFileLoadedShared<T>, DialogFileLoadCanceled<T>>,
+    );
+}
+
+impl FileDialog<'_, '_, '_> {
+    /// Only read bytes in `range` of the file with
+    /// [`FileDialog::load_file_shared`]. The range is clamped to the size of
+    /// the file.
+    ///
+    /// Size limit set with [`FileDialog::set_max_file_size`] applies to the
+    /// length of the range, [`DialogFileTooLarge<T>`](crate::DialogFileTooLarge)
+    /// still reports size of the whole file.
+    ///
+    /// Does not exist in `wasm32`.
+    pub fn set_read_range(mut self, range: impl RangeBounds<u64>) -> Self {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => u64::MAX,
+        };
+
+        self.load_options.range = Some(start..end.max(start));
+        self
+    }
+
+    /// Only read first `len` bytes of the file with
+    /// [`FileDialog::load_file_shared`].
+    ///
+    /// Does not exist in `wasm32`.
+    pub fn set_read_len(self, len: u64) -> Self {
+        self.set_read_range(..len)
+    }
+
+    /// Memory-map the file with [`FileDialog::load_file_shared`] instead of
+    /// reading it into memory when `memory_map` is `true`.
+    ///
+    /// The file must not be modified while it is mapped, otherwise the
+    /// contents of the buffer may change or the app may crash, this is
+    /// inherent to memory-mapping files.
+    ///
+    /// Requires `mmap` feature, does not exist in `wasm32`.
+    #[cfg(feature = "mmap")]
+    pub fn set_memory_map(mut self, memory_map: bool) -> Self {
+        self.load_options.memory_map = memory_map;
+        self
+    }
+
+    /// Open pick file dialog and load its contents into shared
+    /// [`FileBuffer`]. When file contents get loaded, the
+    /// [`DialogFileLoadedShared<T>`] gets sent. You can read this event with
+    /// Bevy's [`EventReader<DialogFileLoadedShared<T>>`].
+    ///
+    /// Use [`FileDialog::set_read_range`] to load only part of the file and
+    /// `FileDialog::set_memory_map` (with `mmap` feature) to map the file instead
+    /// of reading it. When the file can't be read,
+    /// [`DialogFileLoadFailed<T>`] gets sent with [`LoadError::Read`].
+    ///
+    /// Does not exist in `wasm32`.
+    pub fn load_file_shared<T: LoadContents>(self) {
+        self.commands.queue(move |world: &mut World| {
+            let sender = world
+                .get_resource::<StreamSender<DialogResult<DialogFileLoadedShared<T>>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
+
+            let too_large_sender = world
+                .get_resource::<StreamSender<DialogFileTooLarge<T>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
+
+            let failed_sender = world
+                .get_resource::<StreamSender<DialogFileLoadFailed<T>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
+
+            let max_size = self.load_options.max_size.or_else(|| {
+                world
+                    .get_resource::<MaxFileSize<T>>()
+                    .map(|max_size| max_size.0)
+            });
+
+            let waker = AppWaker::from_world(world);
+
+            let dialog = self.config.apply::<T>(self.dialog, world);
+
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    let file = dialog.pick_file().await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
+
+                    let Some(file) = file else {
+                        sender.send(DialogResult::Canceled).unwrap();
+                        return;
+                    };
+
+                    let contents = match read_shared(&file, &self.load_options, max_size) {
+                        Ok(Ok(contents)) => contents,
+                        Ok(Err(event)) => {
+                            too_large_sender.send(event).unwrap();
+                            return;
+                        }
+                        Err(err) => {
+                            let event = DialogFileLoadFailed::new(&file, LoadError::Read(err));
+                            failed_sender.send(event).unwrap();
+                            return;
+                        }
+                    };
+
+                    let event = DialogFileLoadedShared {
+                        file_name: file.file_name(),
+                        contents,
+                        path: file.path().to_path_buf(),
+                        marker: PhantomData,
+                    };
+
+                    sender.send(DialogResult::Single(event)).unwrap();
+                })
+                .detach();
+        });
+    }
+}
+
+/// Read file at `path` according to `options`. When number of bytes to read
+/// exceeds `max_size`, nothing is read and [`DialogFileTooLarge`] with size of
+/// the whole file is returned instead.
+fn read_shared<T: LoadContents>(
+    file_handle: &FileHandle,
+    options: &LoadOptions,
+    max_size: Option<u64>,
+) -> io::Result<Result<FileBuffer, DialogFileTooLarge<T>>> {
+    let mut file = File::open(file_handle.path())?;
+    let size = file.metadata()?.len();
+
+    let Range { start, end } = options.range.clone().unwrap_or(0..size);
+    let start = start.min(size);
+    let len = end.min(size) - start;
+
+    if let Some(max_size) = max_size.filter(|max_size| len > *max_size) {
+        return Ok(Err(DialogFileTooLarge::new(file_handle, size, max_size)));
+    }
+
+    #[cfg(feature = "mmap")]
+    if options.memory_map && len > 0 {
+        // SAFETY: mapped file must not be modified while the buffer is alive,
+        // this is documented on `FileDialog::set_memory_map`.
+        let map = unsafe {
+            memmap2::MmapOptions::new()
+                .offset(start)
+                .len(len as usize)
+                .map(&file)?
+        };
+        return Ok(Ok(FileBuffer(Arc::new(BufferInner::Mapped(map)))));
+    }
+
+    let mut contents = Vec::with_capacity(len as usize);
+    file.seek(SeekFrom::Start(start))?;
+    file.take(len).read_to_end(&mut contents)?;
+
+    Ok(Ok(contents.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_file(name: &str, options: &LoadOptions, max_size: Option<u64>) -> Vec<u8> {
+        let path = std::env::temp_dir().join(format!("bevy_file_dialog_{name}"));
+        std::fs::write(&path, b"0123456789").unwrap();
+
+        let contents = read_shared::<()>(&FileHandle::from(path), options, max_size)
+            .unwrap()
+            .ok()
+            .expect("file should not be too large");
+        contents.to_vec()
+    }
+
+    #[test]
+    fn read_range() {
+        let options = LoadOptions {
+            range: Some(2..5),
+            ..Default::default()
+        };
+        assert_eq!(read_test_file("range", &options, None), b"234");
+
+        let options = LoadOptions {
+            range: Some(8..100),
+            ..Default::default()
+        };
+        assert_eq!(read_test_file("range_clamped", &options, None), b"89");
+    }
+
+    #[test]
+    fn range_counts_toward_max_size() {
+        let options = LoadOptions {
+            range: Some(0..4),
+            ..Default::default()
+        };
+        assert_eq!(read_test_file("range_max_size", &options, Some(4)), b"0123");
+
+        let path = std::env::temp_dir().join("bevy_file_dialog_too_large");
+        std::fs::write(&path, b"0123456789").unwrap();
+        let result = read_shared::<()>(
+            &FileHandle::from(path.clone()),
+            &LoadOptions::default(),
+            Some(4),
+        );
+        assert!(matches!(result, Ok(Err(event)) if event.size == 10));
+
+        // Size of the whole file is reported, not length of the range
+        let options = LoadOptions {
+            range: Some(2..8),
+            ..Default::default()
+        };
+        let result = read_shared::<()>(&FileHandle::from(path), &options, Some(4));
+        assert!(matches!(result, Ok(Err(event)) if event.size == 10 && event.max_size == 4));
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn read_mapped() {
+        let options = LoadOptions {
+            range: Some(3..6),
+            memory_map: true,
+            ..Default::default()
+        };
+        assert_eq!(read_test_file("mapped", &options, None), b"345");
+    }
+}
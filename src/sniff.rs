@@ -0,0 +1,400 @@
+use crate::{FileDialog, LoadError};
+
+/// Type of file contents detected from their first bytes, see
+/// [`FileDialog::set_verify_content`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ContentType {
+    /// PNG image.
+    Png,
+    /// JPEG image.
+    Jpeg,
+    /// GIF image.
+    Gif,
+    /// WebP image.
+    Webp,
+    /// BMP image.
+    Bmp,
+    /// KTX2 texture.
+    Ktx2,
+    /// Binary glTF.
+    Glb,
+    /// glTF in JSON format.
+    Gltf,
+    /// JSON document.
+    Json,
+    /// ZIP archive.
+    Zip,
+    /// Gzip compressed data.
+    Gzip,
+    /// Zstandard compressed data.
+    Zstd,
+    /// PDF document.
+    Pdf,
+    /// Ogg container.
+    Ogg,
+    /// FLAC audio.
+    Flac,
+    /// WAV audio.
+    Wav,
+    /// MP3 audio.
+    Mp3,
+}
+
+impl ContentType {
+    /// Detect type of `contents` from their first bytes. Returns `None` when
+    /// the type is not recognized.
+    pub fn detect(contents: &[u8]) -> Option<Self> {
+        let content_type = match contents {
+            [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => ContentType::Png,
+            [0xFF, 0xD8, 0xFF, ..] => ContentType::Jpeg,
+            [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => ContentType::Gif,
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => ContentType::Webp,
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => ContentType::Wav,
+            [b'B', b'M', ..] => ContentType::Bmp,
+            [0xAB, b'K', b'T', b'X', b' ', b'2', b'0', 0xBB, ..] => ContentType::Ktx2,
+            [b'g', b'l', b'T', b'F', ..] => ContentType::Glb,
+            [b'P', b'K', 0x03 | 0x05 | 0x07, 0x04 | 0x06 | 0x08, ..] => ContentType::Zip,
+            [0x1F, 0x8B, ..] => ContentType::Gzip,
+            [0x28, 0xB5, 0x2F, 0xFD, ..] => ContentType::Zstd,
+            [b'%', b'P', b'D', b'F', b'-', ..] => ContentType::Pdf,
+            [b'O', b'g', b'g', b'S', ..] => ContentType::Ogg,
+            [b'f', b'L', b'a', b'C', ..] => ContentType::Flac,
+            [b'I', b'D', b'3', ..] | [0xFF, 0xFB | 0xF3 | 0xF2, ..] => ContentType::Mp3,
+            _ => return detect_json(contents),
+        };
+
+        Some(content_type)
+    }
+
+    /// MIME type of the content.
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ContentType::Png => "image/png",
+            ContentType::Jpeg => "image/jpeg",
+            ContentType::Gif => "image/gif",
+            ContentType::Webp => "image/webp",
+            ContentType::Bmp => "image/bmp",
+            ContentType::Ktx2 => "image/ktx2",
+            ContentType::Glb => "model/gltf-binary",
+            ContentType::Gltf => "model/gltf+json",
+            ContentType::Json => "application/json",
+            ContentType::Zip => "application/zip",
+            ContentType::Gzip => "application/gzip",
+            ContentType::Zstd => "application/zstd",
+            ContentType::Pdf => "application/pdf",
+            ContentType::Ogg => "audio/ogg",
+            ContentType::Flac => "audio/flac",
+            ContentType::Wav => "audio/wav",
+            ContentType::Mp3 => "audio/mpeg",
+        }
+    }
+
+    /// File extensions that are expected to contain this type of content.
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            ContentType::Png => &["png"],
+            ContentType::Jpeg => &["jpg", "jpeg"],
+            ContentType::Gif => &["gif"],
+            ContentType::Webp => &["webp"],
+            ContentType::Bmp => &["bmp"],
+            ContentType::Ktx2 => &["ktx2"],
+            ContentType::Glb => &["glb"],
+            ContentType::Gltf => &["gltf", "json"],
+            ContentType::Json => &["json"],
+            ContentType::Zip => &["zip"],
+            ContentType::Gzip => &["gz"],
+            ContentType::Zstd => &["zst"],
+            ContentType::Pdf => &["pdf"],
+            ContentType::Ogg => &["ogg", "oga"],
+            ContentType::Flac => &["flac"],
+            ContentType::Wav => &["wav"],
+            ContentType::Mp3 => &["mp3"],
+        }
+    }
+}
+
+const ALL: &[ContentType] = &[
+    ContentType::Png,
+    ContentType::Jpeg,
+    ContentType::Gif,
+    ContentType::Webp,
+    ContentType::Bmp,
+    ContentType::Ktx2,
+    ContentType::Glb,
+    ContentType::Gltf,
+    ContentType::Json,
+    ContentType::Zip,
+    ContentType::Gzip,
+    ContentType::Zstd,
+    ContentType::Pdf,
+    ContentType::Ogg,
+    ContentType::Flac,
+    ContentType::Wav,
+    ContentType::Mp3,
+];
+
+/// Number of first bytes of the contents in which JSON and glTF are detected.
+const JSON_PREFIX: usize = 4096;
+
+fn detect_json(contents: &[u8]) -> Option<ContentType> {
+    let prefix = &contents[..contents.len().min(JSON_PREFIX)];
+    // The prefix may end in the middle of a character
+    let text = match std::str::from_utf8(prefix) {
+        Ok(text) => text,
+        Err(err) if err.error_len().is_none() => {
+            std::str::from_utf8(&prefix[..err.valid_up_to()]).ok()?
+        }
+        Err(_) => return None,
+    };
+
+    let text = text.strip_prefix('\u{FEFF}').unwrap_or(text).trim_start();
+    if text.starts_with('{') && has_top_level_asset(text.as_bytes()) {
+        Some(ContentType::Gltf)
+    } else if text.starts_with(['{', '[']) {
+        Some(ContentType::Json)
+    } else {
+        None
+    }
+}
+
+/// Returns `true` when JSON object in `text` has `asset` object among its
+/// top level keys, as required by glTF. Only keys within `text` are checked.
+fn has_top_level_asset(text: &[u8]) -> bool {
+    let mut depth = 0usize;
+    let mut index = 0;
+
+    while index < text.len() {
+        match text[index] {
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            b'"' => {
+                let start = index + 1;
+                let Some(end) = string_end(text, start) else {
+                    return false;
+                };
+
+                let value = text[end + 1..]
+                    .trim_ascii_start()
+                    .strip_prefix(b":")
+                    .map(<[u8]>::trim_ascii_start);
+                if depth == 1 && &text[start..end] == b"asset" {
+                    return value.is_some_and(|value| value.starts_with(b"{"));
+                }
+
+                index = end;
+            }
+            _ => {}
+        }
+
+        index += 1;
+    }
+
+    false
+}
+
+/// Index of quote that ends JSON string starting at `start` in `text`.
+fn string_end(text: &[u8], start: usize) -> Option<usize> {
+    let mut index = start;
+    while index < text.len() {
+        match text[index] {
+            b'\\' => index += 1,
+            b'"' => return Some(index),
+            _ => {}
+        }
+
+        index += 1;
+    }
+
+    None
+}
+
+/// Detect type of `contents` of file with `file_name` and check that it
+/// matches its extension when `verify` is `true`. Files with extensions that
+/// are not known to any [`ContentType`] are not checked.
+pub(crate) fn sniff(
+    verify: bool,
+    file_name: &str,
+    contents: &[u8],
+) -> Result<Option<ContentType>, LoadError> {
+    if !verify {
+        return Ok(None);
+    }
+
+    let detected = ContentType::detect(contents);
+
+    let Some((_, extension)) = file_name.rsplit_once('.') else {
+        return Ok(detected);
+    };
+
+    let is_expected = |content_type: &ContentType| {
+        content_type
+            .extensions()
+            .iter()
+            .any(|ext| ext.eq_ignore_ascii_case(extension))
+    };
+
+    let known_extension = ALL.iter().any(is_expected);
+    if known_extension && !detected.as_ref().is_some_and(is_expected) {
+        return Err(LoadError::ContentMismatch {
+            extension: extension.to_string(),
+            detected,
+        });
+    }
+
+    Ok(detected)
+}
+
+impl FileDialog<'_, '_, '_> {
+    /// Detect type of loaded file from its first bytes and check that it
+    /// matches extension of the file. Applies to [`FileDialog::load_file`]
+    /// and [`FileDialog::load_multiple_files`]. The contents are checked
+    /// after they are decrypted, decompressed and stripped of
+    /// [`VersionedFormat`](crate::VersionedFormat) header. Extension of the
+    /// compression is ignored then, so `level.json.gz` loaded with
+    /// [`FileDialog::set_compression`] is expected to contain JSON, while
+    /// loaded without it is expected to contain gzip data.
+    ///
+    /// The detected type is available in `content_type` field of
+    /// [`DialogFileLoaded<T>`](crate::DialogFileLoaded). When the contents
+    /// don't match the extension, for example `.png` file that is actually a
+    /// ZIP archive, [`DialogFileLoadFailed<T>`](crate::DialogFileLoadFailed)
+    /// gets sent with [`LoadError::ContentMismatch`] instead. Files with
+    /// extensions that are not known to any [`ContentType`] are not checked.
+    pub fn set_verify_content(mut self, verify: bool) -> Self {
+        self.load_options.verify_content = verify;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect() {
+        let cases: &[(&[u8], ContentType)] = &[
+            (b"\x89PNG\r\n\x1A\n....", ContentType::Png),
+            (b"\xFF\xD8\xFF\xE0", ContentType::Jpeg),
+            (b"GIF89a", ContentType::Gif),
+            (b"RIFF\0\0\0\0WEBPVP8 ", ContentType::Webp),
+            (b"RIFF\0\0\0\0WAVEfmt ", ContentType::Wav),
+            (b"BM\0\0", ContentType::Bmp),
+            (b"\xABKTX 20\xBB\r\n", ContentType::Ktx2),
+            (b"glTF\x02\0\0\0", ContentType::Glb),
+            (b"PK\x03\x04", ContentType::Zip),
+            (b"\x1F\x8B\x08", ContentType::Gzip),
+            (b"\x28\xB5\x2F\xFD", ContentType::Zstd),
+            (b"%PDF-1.7", ContentType::Pdf),
+            (b"OggS\0", ContentType::Ogg),
+            (b"fLaC\0", ContentType::Flac),
+            (b"ID3\x04", ContentType::Mp3),
+            (b"  {\"a\": 1}", ContentType::Json),
+            (b"[1, 2]", ContentType::Json),
+            (b"{\"asset\": {\"version\": \"2.0\"}}", ContentType::Gltf),
+        ];
+
+        for (contents, expected) in cases {
+            assert_eq!(
+                ContentType::detect(contents),
+                Some(*expected),
+                "{expected:?}"
+            );
+        }
+
+        assert_eq!(ContentType::detect(b""), None);
+        assert_eq!(ContentType::detect(b"plain text"), None);
+        assert_eq!(ContentType::detect(b"\xFF\xFE\x00"), None);
+    }
+
+    #[test]
+    fn detect_gltf_only_with_top_level_asset() {
+        let cases: &[(&[u8], ContentType)] = &[
+            (b"{\"scene\": 0, \"asset\" : {}}", ContentType::Gltf),
+            (b"{\"asset\": \"name.png\"}", ContentType::Json),
+            (b"{\"nested\": {\"asset\": {}}}", ContentType::Json),
+            (b"{\"list\": [\"asset\"]}", ContentType::Json),
+            (b"{\"key \\\"asset\": {}}", ContentType::Json),
+            (b"[{\"asset\": {}}]", ContentType::Json),
+        ];
+
+        for (contents, expected) in cases {
+            assert_eq!(
+                ContentType::detect(contents),
+                Some(*expected),
+                "{}",
+                String::from_utf8_lossy(contents)
+            );
+        }
+    }
+
+    #[test]
+    fn detect_json_in_prefix() {
+        // Character split at the end of the prefix and invalid bytes after it
+        let mut contents = b"{\"asset\": {}, \"name\": \"".to_vec();
+        contents.resize(JSON_PREFIX - 1, b'a');
+        contents.extend_from_slice("\u{e9}".as_bytes());
+        contents.extend_from_slice(b"\xFF\xFF");
+        assert_eq!(ContentType::detect(&contents), Some(ContentType::Gltf));
+
+        // Key after the prefix is not checked
+        let mut contents = b"{\"name\": \"".to_vec();
+        contents.resize(JSON_PREFIX, b'a');
+        contents.extend_from_slice(b"\", \"asset\": {}}");
+        assert_eq!(ContentType::detect(&contents), Some(ContentType::Json));
+    }
+
+    #[test]
+    fn detect_json_with_bom() {
+        assert_eq!(
+            ContentType::detect(b"\xEF\xBB\xBF{\"a\": 1}"),
+            Some(ContentType::Json)
+        );
+    }
+
+    #[test]
+    fn every_type_listed() {
+        for content_type in ALL {
+            assert!(!content_type.extensions().is_empty());
+            assert!(content_type.mime_type().contains('/'));
+        }
+    }
+
+    #[test]
+    fn sniff_matches_extension() {
+        assert_eq!(sniff(false, "image.png", b"not png").unwrap(), None);
+        assert_eq!(
+            sniff(true, "image.PNG", b"\x89PNG\r\n\x1A\n").unwrap(),
+            Some(ContentType::Png)
+        );
+        assert_eq!(
+            sniff(true, "scene.json", b"{\"asset\": {}}").unwrap(),
+            Some(ContentType::Gltf)
+        );
+
+        // Unknown extensions and files without extension are not checked
+        assert_eq!(
+            sniff(true, "level.ron", b"PK\x03\x04").unwrap(),
+            Some(ContentType::Zip)
+        );
+        assert_eq!(sniff(true, "level", b"plain").unwrap(), None);
+    }
+
+    #[test]
+    fn sniff_mismatch() {
+        let Err(LoadError::ContentMismatch {
+            extension,
+            detected,
+        }) = sniff(true, "image.png", b"PK\x03\x04")
+        else {
+            panic!("zip in .png should not match");
+        };
+        assert_eq!(extension, "png");
+        assert_eq!(detected, Some(ContentType::Zip));
+
+        assert!(matches!(
+            sniff(true, "level.json", b"plain"),
+            Err(LoadError::ContentMismatch { detected: None, .. })
+        ));
+    }
+}
@@ -0,0 +1,379 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_tasks::prelude::*;
+use crossbeam_channel::unbounded;
+
+use crate::{
+    decode_loaded, AppWaker, DialogFileLoadFailed, DialogFileLoaded, DialogFileTooLarge,
+    DialogOutcome, DialogResult, FileDialog, LoadContents, LoadError, MaxFileSize, StreamReceiver,
+    StreamSender, WakeUpOnDrop,
+};
+
+/// Size of a single read when streaming file contents.
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Id of the next streaming load, progress is coalesced per load.
+static NEXT_LOAD_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Event that gets sent periodically while file contents are being read with
+/// [`FileDialog::load_file_streaming`].
+///
+/// Progress is reported in bytes read from the file, before the contents get
+/// decoded. Progress events may be coalesced when they are produced faster
+/// than they are consumed, older progress of each load is then replaced by
+/// its latest one, so the final progress with `read == total` is never lost.
+#[derive(Message)]
+pub struct DialogFileLoadProgress<T: LoadContents> {
+    /// Name of file being loaded.
+    pub file_name: String,
+
+    /// Path to file being loaded.
+    pub path: PathBuf,
+
+    /// Number of bytes read so far.
+    pub read: u64,
+
+    /// Total size of the file in bytes.
+    pub total: u64,
+
+    load: u64,
+
+    marker: PhantomData<T>,
+}
+
+impl<T: LoadContents> DialogFileLoadProgress<T> {
+    /// Fraction of the file that was read, in range `0.0..=1.0`.
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.read as f32 / self.total as f32
+        }
+    }
+}
+
+/// Handle to file load started with [`FileDialog::load_file_streaming`] that
+/// allows you to cancel the load while it is in progress.
+#[derive(Clone, Default)]
+pub struct FileLoadHandle(Arc<AtomicBool>);
+
+impl FileLoadHandle {
+    /// Stop reading the file. If the file is still being read,
+    /// [`DialogFileLoadCanceled<T>`](crate::DialogFileLoadCanceled) gets sent
+    /// instead of [`DialogFileLoaded<T>`].
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if [`FileLoadHandle::cancel`] was called.
+    pub fn is_canceled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+pub(crate) fn register_load_progress<T: LoadContents>(app: &mut App) {
+    // Reading is never blocked by the app, progress gets coalesced when it is
+    // received instead
+    let (tx, rx) = unbounded::<DialogFileLoadProgress<T>>();
+    app.insert_resource(StreamSender(tx));
+    app.insert_resource(StreamReceiver(rx));
+    app.add_message::<DialogFileLoadProgress<T>>();
+    app.add_systems(First, handle_load_progress::<T>);
+}
+
+/// Send progress received since the last frame, only the latest progress of
+/// each load.
+fn handle_load_progress<T: LoadContents>(
+    receiver: Res<StreamReceiver<DialogFileLoadProgress<T>>>,
+    mut ev_progress: MessageWriter<DialogFileLoadProgress<T>>,
+) {
+    let mut latest: Vec<DialogFileLoadProgress<T>> = Vec::new();
+    for event in receiver.try_iter() {
+        match latest.iter_mut().find(|latest| latest.load == event.load) {
+            Some(latest) => *latest = event,
+            None => latest.push(event),
+        }
+    }
+
+    ev_progress.write_batch(latest);
+}
+
+impl FileDialog<'_, '_, '_> {
+    /// Open pick file dialog and read its contents in chunks. While the file
+    /// is being read, [`DialogFileLoadProgress<T>`] gets sent, and when the
+    /// whole file is read, [`DialogFileLoaded<T>`] gets sent.
+    ///
+    /// The returned [`FileLoadHandle`] can be used to cancel the load, in that
+    /// case [`DialogFileLoadCanceled<T>`](crate::DialogFileLoadCanceled) gets
+    /// sent. When the file can't be read,
+    /// [`DialogFileLoadFailed<T>`] gets sent with [`LoadError::Read`].
+    ///
+    /// Once the whole file is read, its contents are decoded and verified the
+    /// same way as with [`FileDialog::load_file`].
+    ///
+    /// Does not exist in `wasm32`.
+    pub fn load_file_streaming<T: LoadContents>(self) -> FileLoadHandle {
+        let handle = FileLoadHandle::default();
+        let cancel = handle.clone();
+
+        self.commands.queue(move |world: &mut World| {
+            let sender = world
+                .get_resource::<StreamSender<DialogResult<DialogFileLoaded<T>>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
+
+            let progress_sender = world
+                .get_resource::<StreamSender<DialogFileLoadProgress<T>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
+
+            let too_large_sender = world
+                .get_resource::<StreamSender<DialogFileTooLarge<T>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
+
+            let failed_sender = world
+                .get_resource::<StreamSender<DialogFileLoadFailed<T>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
+
+            let max_size = self.load_options.max_size.or_else(|| {
+                world
+                    .get_resource::<MaxFileSize<T>>()
+                    .map(|max_size| max_size.0)
+            });
+
+            let waker = AppWaker::from_world(world);
+
+            let codec = self.codec.with_format::<T>(world);
+
+            let dialog = self.config.apply::<T>(self.dialog, world);
+
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    let file = dialog.pick_file().await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
+
+                    let Some(file) = file else {
+                        sender.send(DialogResult::Canceled).unwrap();
+                        return;
+                    };
+
+                    if let Ok(metadata) = std::fs::metadata(file.path()) {
+                        if let Err(event) =
+                            DialogFileTooLarge::check(&file, metadata.len(), max_size)
+                        {
+                            too_large_sender.send(event).unwrap();
+                            return;
+                        }
+                    }
+
+                    let file_name = file.file_name();
+                    let path = file.path().to_path_buf();
+                    let load = NEXT_LOAD_ID.fetch_add(1, Ordering::Relaxed);
+
+                    let progress = |read, total| {
+                        let event = DialogFileLoadProgress {
+                            file_name: file_name.clone(),
+                            path: path.clone(),
+                            read,
+                            total,
+                            load,
+                            marker: PhantomData,
+                        };
+
+                        progress_sender.send(event).unwrap();
+                        drop(waker.as_ref().map(WakeUpOnDrop));
+                    };
+
+                    let contents = match read_chunked(&path, &cancel, progress) {
+                        Ok(Some(contents)) => contents,
+                        Ok(None) => {
+                            sender.send(DialogResult::Canceled).unwrap();
+                            return;
+                        }
+                        Err(err) => {
+                            let event = DialogFileLoadFailed::new(&file, LoadError::Read(err));
+                            failed_sender.send(event).unwrap();
+                            return;
+                        }
+                    };
+
+                    let outcome = decode_loaded(
+                        &file,
+                        contents,
+                        max_size,
+                        self.load_options.verify_content,
+                        &codec,
+                        self.collect_metadata,
+                    );
+
+                    match outcome {
+                        DialogOutcome::Loaded(event) => {
+                            sender.send(DialogResult::Single(event)).unwrap()
+                        }
+                        DialogOutcome::TooLarge(event) => too_large_sender.send(event).unwrap(),
+                        DialogOutcome::Failed(event) => failed_sender.send(event).unwrap(),
+                        _ => {}
+                    }
+                })
+                .detach();
+        });
+
+        handle
+    }
+}
+
+/// Read file at `path` in chunks, calling `progress` after each chunk.
+/// Returns `None` when the read was canceled through `cancel`.
+fn read_chunked(
+    path: &Path,
+    cancel: &FileLoadHandle,
+    mut progress: impl FnMut(u64, u64),
+) -> io::Result<Option<Vec<u8>>> {
+    let mut file = File::open(path)?;
+    let total = file.metadata()?.len();
+
+    let mut contents = Vec::with_capacity(total as usize);
+    let mut chunk = vec![0; CHUNK_SIZE];
+
+    progress(0, total);
+
+    loop {
+        if cancel.is_canceled() {
+            return Ok(None);
+        }
+
+        let read = match file.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+
+        contents.extend_from_slice(&chunk[..read]);
+        progress(contents.len() as u64, total);
+    }
+
+    Ok(Some(contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use crate::{ContentType, FileDialogExt, FileDialogPlugin};
+
+    struct Stream;
+
+    fn progress(load: u64, read: u64) -> DialogFileLoadProgress<Stream> {
+        DialogFileLoadProgress {
+            file_name: format!("{load}.bin"),
+            path: PathBuf::from(format!("{load}.bin")),
+            read,
+            total: 10,
+            load,
+            marker: PhantomData,
+        }
+    }
+
+    #[test]
+    fn read_in_chunks() {
+        let path = std::env::temp_dir().join("bevy_file_dialog_read_chunked");
+        let written = vec![7; CHUNK_SIZE + 10];
+        std::fs::write(&path, &written).unwrap();
+
+        let mut reported = Vec::new();
+        let contents = read_chunked(&path, &FileLoadHandle::default(), |read, total| {
+            reported.push((read, total))
+        })
+        .unwrap();
+
+        let total = written.len() as u64;
+        assert_eq!(contents, Some(written));
+        assert_eq!(
+            reported,
+            [(0, total), (CHUNK_SIZE as u64, total), (total, total)]
+        );
+    }
+
+    #[test]
+    fn read_canceled() {
+        let path = std::env::temp_dir().join("bevy_file_dialog_read_canceled");
+        std::fs::write(&path, b"contents").unwrap();
+
+        let cancel = FileLoadHandle::default();
+        cancel.cancel();
+
+        let contents = read_chunked(&path, &cancel, |_, _| {}).unwrap();
+        assert_eq!(contents, None);
+    }
+
+    #[test]
+    fn read_missing_file() {
+        let path = std::env::temp_dir().join("bevy_file_dialog_read_missing");
+        let _ = std::fs::remove_file(&path);
+
+        assert!(read_chunked(&path, &FileLoadHandle::default(), |_, _| {}).is_err());
+    }
+
+    #[test]
+    fn progress_coalesced_per_load() {
+        let mut app = testing::app(FileDialogPlugin::new().with_load_file::<Stream>());
+        testing::collect::<DialogFileLoadProgress<Stream>>(&mut app);
+
+        let sender = app
+            .world()
+            .resource::<StreamSender<DialogFileLoadProgress<Stream>>>()
+            .0
+            .clone();
+        for event in [progress(0, 5), progress(1, 10), progress(0, 10)] {
+            sender.send(event).unwrap();
+        }
+        app.update();
+
+        let received: Vec<_> = testing::received::<DialogFileLoadProgress<Stream>>(&app)
+            .iter()
+            .map(|event| (event.load, event.read))
+            .collect();
+        assert_eq!(received, [(0, 10), (1, 10)]);
+    }
+
+    #[test]
+    fn streamed_contents_decoded() {
+        let path = testing::test_directory("stream_decoded").join("level.json");
+        std::fs::write(&path, b"{}").unwrap();
+
+        let mut app = testing::app(FileDialogPlugin::new().with_load_file::<Stream>());
+        testing::collect::<DialogFileLoaded<Stream>>(&mut app);
+        testing::collect::<DialogFileLoadProgress<Stream>>(&mut app);
+        testing::respond(&mut app, [&path]);
+
+        app.world_mut()
+            .commands()
+            .dialog()
+            .set_verify_content(true)
+            .load_file_streaming::<Stream>();
+        testing::update_until_received::<DialogFileLoaded<Stream>>(&mut app);
+
+        let loaded = &testing::received::<DialogFileLoaded<Stream>>(&app)[0];
+        assert_eq!(*loaded.contents, b"{}");
+        assert_eq!(loaded.content_type, Some(ContentType::Json));
+
+        let last = testing::received::<DialogFileLoadProgress<Stream>>(&app)
+            .last()
+            .map(|event| (event.read, event.total));
+        assert_eq!(last, Some((2, 2)));
+    }
+}
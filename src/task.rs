@@ -0,0 +1,556 @@
+use std::path::Path;
+
+use bevy_ecs::prelude::*;
+use bevy_tasks::prelude::*;
+use bevy_tasks::Task;
+
+use crate::codec::Codec;
+use crate::kind::Dialog;
+use crate::{
+    load_file_outcome, save_file_outcome, AppWaker, Compression, DialogOutcome, FileDialog,
+    LoadContents, MaxFileSize, SaveContents, WakeUpOnDrop,
+};
+
+/// Spawn task that completes with the outcome received from `receiver`. When
+/// the sender gets dropped without sending, because the command was never
+/// applied, the task completes with [`DialogOutcome::Canceled`].
+fn outcome_task<T: Send + Sync + 'static>(
+    receiver: async_channel::Receiver<DialogOutcome<T>>,
+) -> Task<DialogOutcome<T>> {
+    AsyncComputeTaskPool::get()
+        .spawn(async move { receiver.recv().await.unwrap_or(DialogOutcome::Canceled) })
+}
+
+/// Send outcome to task created with [`outcome_task`].
+fn send_outcome<T: Send + Sync + 'static>(
+    sender: async_channel::Sender<DialogOutcome<T>>,
+) -> impl FnOnce(DialogOutcome<T>) + Send + 'static {
+    move |outcome| {
+        // The task may have been dropped, nobody is interested in the outcome then
+        let _ = sender.try_send(outcome);
+    }
+}
+
+impl FileDialog<'_, '_, '_> {
+    /// Open save file dialog and save the `contents` to that file, same as
+    /// [`FileDialog::save_file`], but instead of sending events return
+    /// [`Task`] that completes with [`DialogOutcome::Saved`] or
+    /// [`DialogOutcome::Canceled`].
+    ///
+    /// The task can be awaited in other async code or polled from system with
+    /// [`bevy_tasks::futures::check_ready`]. To open multiple dialogs one
+    /// after another from async code, use [`AsyncDialog`]. Does not require
+    /// [`FileDialogPlugin::with_save_file::<T>`](crate::FileDialogPlugin::with_save_file),
+    /// dropping the task does not close the dialog.
+    pub fn save_file_async<T: SaveContents>(self, contents: Vec<u8>) -> Task<DialogOutcome<T>> {
+        let (sender, receiver) = async_channel::bounded(1);
+        self.open_save_file(contents, move |_| send_outcome(sender));
+        outcome_task(receiver)
+    }
+
+    /// Open pick file dialog and load its contents, same as
+    /// [`FileDialog::load_file`], but instead of sending events return
+    /// [`Task`] that completes with [`DialogOutcome::Loaded`],
+    /// [`DialogOutcome::TooLarge`], [`DialogOutcome::Failed`] or
+    /// [`DialogOutcome::Canceled`].
+    ///
+    /// See [`FileDialog::save_file_async`] for how to use the task.
+    pub fn load_file_async<T: LoadContents>(self) -> Task<DialogOutcome<T>> {
+        let (sender, receiver) = async_channel::bounded(1);
+        self.open_load_file(move |_| send_outcome(sender));
+        outcome_task(receiver)
+    }
+
+    /// Open pick file dialog, same as [`FileDialog::pick_file_path`], but
+    /// instead of sending events return [`Task`] that completes with
+    /// [`DialogOutcome::Picked`] or [`DialogOutcome::Canceled`].
+    ///
+    /// See [`FileDialog::save_file_async`] for how to use the task. Does not
+    /// exist in `wasm32`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn pick_file_path_async<T: crate::PickFilePath>(self) -> Task<DialogOutcome<T>> {
+        let (sender, receiver) = async_channel::bounded(1);
+        self.open_pick_file(move |_| send_outcome(sender));
+        outcome_task(receiver)
+    }
+
+    /// Open pick directory dialog, same as
+    /// [`FileDialog::pick_directory_path`], but instead of sending events
+    /// return [`Task`] that completes with [`DialogOutcome::DirectoryPicked`]
+    /// or [`DialogOutcome::Canceled`].
+    ///
+    /// See [`FileDialog::save_file_async`] for how to use the task. Does not
+    /// exist in `wasm32`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn pick_directory_path_async<T: crate::PickDirectoryPath>(self) -> Task<DialogOutcome<T>> {
+        let (sender, receiver) = async_channel::bounded(1);
+        self.open_pick_directory(move |_| send_outcome(sender));
+        outcome_task(receiver)
+    }
+
+    /// Open save file dialog and save the `contents` to that file. When the
+    /// command is applied, `deliver` gets called and the function it returns
+    /// receives the outcome of the dialog.
+    pub(crate) fn open_save_file<T, D>(
+        self,
+        contents: Vec<u8>,
+        deliver: impl FnOnce(&mut World) -> D + Send + 'static,
+    ) where
+        T: SaveContents,
+        D: FnOnce(DialogOutcome<T>) + Send + 'static,
+    {
+        self.commands.queue(move |world: &mut World| {
+            let waker = AppWaker::from_world(world);
+
+            let codec = self.codec.with_format::<T>(world);
+
+            let dialog = self.config.apply::<T>(self.dialog, world);
+
+            let deliver = deliver(world);
+
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    let outcome = save_file_outcome(dialog, &codec, contents).await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
+                    deliver(outcome);
+                })
+                .detach();
+        });
+    }
+
+    /// Open pick file dialog and load its contents, the outcome is delivered
+    /// the same way as with [`FileDialog::open_save_file`].
+    pub(crate) fn open_load_file<T, D>(self, deliver: impl FnOnce(&mut World) -> D + Send + 'static)
+    where
+        T: LoadContents,
+        D: FnOnce(DialogOutcome<T>) + Send + 'static,
+    {
+        self.commands.queue(move |world: &mut World| {
+            let max_size = self.load_options.max_size.or_else(|| {
+                world
+                    .get_resource::<MaxFileSize<T>>()
+                    .map(|max_size| max_size.0)
+            });
+
+            let waker = AppWaker::from_world(world);
+
+            let codec = self.codec.with_format::<T>(world);
+
+            let dialog = self.config.apply::<T>(self.dialog, world);
+
+            let deliver = deliver(world);
+
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    let outcome = load_file_outcome(
+                        dialog,
+                        max_size,
+                        self.load_options.verify_content,
+                        &codec,
+                        #[cfg(not(target_arch = "wasm32"))]
+                        self.collect_metadata,
+                    )
+                    .await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
+                    deliver(outcome);
+                })
+                .detach();
+        });
+    }
+
+    /// Open pick file dialog, the outcome is delivered the same way as with
+    /// [`FileDialog::open_save_file`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn open_pick_file<T, D>(self, deliver: impl FnOnce(&mut World) -> D + Send + 'static)
+    where
+        T: crate::PickFilePath,
+        D: FnOnce(DialogOutcome<T>) + Send + 'static,
+    {
+        self.commands.queue(move |world: &mut World| {
+            let waker = AppWaker::from_world(world);
+
+            let dialog = self.config.apply::<T>(self.dialog, world);
+
+            let deliver = deliver(world);
+
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    let outcome =
+                        crate::pick::pick_file_outcome(dialog, self.collect_metadata).await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
+                    deliver(outcome);
+                })
+                .detach();
+        });
+    }
+
+    /// Open pick directory dialog, the outcome is delivered the same way as
+    /// with [`FileDialog::open_save_file`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn open_pick_directory<T, D>(
+        self,
+        deliver: impl FnOnce(&mut World) -> D + Send + 'static,
+    ) where
+        T: crate::PickDirectoryPath,
+        D: FnOnce(DialogOutcome<T>) + Send + 'static,
+    {
+        self.commands.queue(move |world: &mut World| {
+            let waker = AppWaker::from_world(world);
+
+            let dialog = self.config.apply::<T>(self.dialog, world);
+
+            let deliver = deliver(world);
+
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    let outcome = crate::pick::pick_directory_outcome(dialog).await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
+                    deliver(outcome);
+                })
+                .detach();
+        });
+    }
+}
+
+/// File dialog that is opened without [`Commands`], so it can be created and
+/// awaited anywhere in async code, for example to open multiple dialogs one
+/// after another in single task:
+///
+/// ```rust
+/// AsyncComputeTaskPool::get()
+///     .spawn(async {
+///         let DialogOutcome::DirectoryPicked(picked) = AsyncDialog::new()
+///             .pick_directory_path::<ExportFile>()
+///             .await
+///         else {
+///             return;
+///         };
+///
+///         AsyncDialog::new()
+///             .set_directory(&picked.path)
+///             .save_file::<ExportFile>(b"exported".to_vec())
+///             .await;
+///     })
+///     .detach();
+/// ```
+///
+/// The dialog has no access to the [`World`], so defaults of
+/// [`DialogKind`](crate::DialogKind), limit set with
+/// [`FileDialogPlugin::with_max_file_size`](crate::FileDialogPlugin::with_max_file_size),
+/// [`VersionedFormat`](crate::VersionedFormat) and
+/// [`MockDialogBackend`](crate::MockDialogBackend) are not used, and the
+/// markers don't need to be registered.
+#[must_use]
+pub struct AsyncDialog {
+    dialog: rfd::AsyncFileDialog,
+    max_size: Option<u64>,
+    codec: Codec,
+}
+
+impl Default for AsyncDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AsyncDialog {
+    /// Create dialog for loading/saving files.
+    pub fn new() -> Self {
+        Self {
+            dialog: rfd::AsyncFileDialog::new(),
+            max_size: None,
+            codec: Codec::default(),
+        }
+    }
+
+    /// Add file extension filter, same as [`FileDialog::add_filter`].
+    pub fn add_filter(mut self, name: impl Into<String>, extensions: &[impl ToString]) -> Self {
+        self.dialog = self.dialog.add_filter(name, extensions);
+        self
+    }
+
+    /// Set starting directory of the dialog, same as
+    /// [`FileDialog::set_directory`].
+    pub fn set_directory<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.dialog = self.dialog.set_directory(path);
+        self
+    }
+
+    /// Set starting file name of the dialog, same as
+    /// [`FileDialog::set_file_name`].
+    pub fn set_file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.dialog = self.dialog.set_file_name(file_name);
+        self
+    }
+
+    /// Set the title of the dialog, same as [`FileDialog::set_title`].
+    pub fn set_title(mut self, title: impl Into<String>) -> Self {
+        self.dialog = self.dialog.set_title(title);
+        self
+    }
+
+    /// Limit size of loaded files, same as [`FileDialog::set_max_file_size`].
+    pub fn set_max_file_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Compress saved and decompress loaded file contents, same as
+    /// [`FileDialog::set_compression`].
+    pub fn set_compression(mut self, compression: Compression) -> Self {
+        self.codec.compression = compression;
+        self
+    }
+
+    /// Open save file dialog and save the `contents` to that file, completes
+    /// with [`DialogOutcome::Saved`] or [`DialogOutcome::Canceled`].
+    pub async fn save_file<T: SaveContents>(mut self, contents: Vec<u8>) -> DialogOutcome<T> {
+        let dialog = Dialog::Native(std::mem::take(&mut self.dialog));
+        self.save_file_with(dialog, contents).await
+    }
+
+    /// Open pick file dialog and load its contents, completes with
+    /// [`DialogOutcome::Loaded`], [`DialogOutcome::TooLarge`],
+    /// [`DialogOutcome::Failed`] or [`DialogOutcome::Canceled`].
+    pub async fn load_file<T: LoadContents>(mut self) -> DialogOutcome<T> {
+        let dialog = Dialog::Native(std::mem::take(&mut self.dialog));
+        self.load_file_with(dialog).await
+    }
+
+    /// Save with options of this dialog into file picked in `dialog`.
+    async fn save_file_with<T: SaveContents>(
+        self,
+        dialog: Dialog,
+        contents: Vec<u8>,
+    ) -> DialogOutcome<T> {
+        save_file_outcome(dialog, &self.codec, contents).await
+    }
+
+    /// Load with options of this dialog file picked in `dialog`.
+    async fn load_file_with<T: LoadContents>(self, dialog: Dialog) -> DialogOutcome<T> {
+        load_file_outcome(
+            dialog,
+            self.max_size,
+            false,
+            &self.codec,
+            #[cfg(not(target_arch = "wasm32"))]
+            false,
+        )
+        .await
+    }
+
+    /// Open pick file dialog, completes with [`DialogOutcome::Picked`] or
+    /// [`DialogOutcome::Canceled`].
+    ///
+    /// Does not exist in `wasm32`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn pick_file_path<T: crate::PickFilePath>(self) -> DialogOutcome<T> {
+        crate::pick::pick_file_outcome(Dialog::Native(self.dialog), false).await
+    }
+
+    /// Open pick directory dialog, completes with
+    /// [`DialogOutcome::DirectoryPicked`] or [`DialogOutcome::Canceled`].
+    ///
+    /// Does not exist in `wasm32`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn pick_directory_path<T: crate::PickDirectoryPath>(self) -> DialogOutcome<T> {
+        crate::pick::pick_directory_outcome(Dialog::Native(self.dialog)).await
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::fs;
+
+    use bevy_app::prelude::*;
+    use bevy_ecs::world::CommandQueue;
+    use bevy_tasks::block_on;
+
+    use super::*;
+    use crate::testing::{self, respond, test_directory};
+    use crate::{FileDialogExt, FileDialogPlugin};
+
+    struct Notes;
+
+    /// Apply the commands that open dialog and wait for `task`.
+    fn complete<T: Send + Sync + 'static>(
+        app: &mut App,
+        task: Task<DialogOutcome<T>>,
+    ) -> DialogOutcome<T> {
+        app.update();
+        block_on(task)
+    }
+
+    #[test]
+    fn save_and_load_async() {
+        let path = test_directory("task_save_load").join("notes.txt");
+        let mut app = testing::app(FileDialogPlugin::new());
+
+        respond(&mut app, [&path]);
+        let task = app
+            .world_mut()
+            .commands()
+            .dialog()
+            .save_file_async::<Notes>(b"notes".to_vec());
+        let DialogOutcome::Saved(saved) = complete(&mut app, task) else {
+            panic!("file not saved");
+        };
+        assert!(saved.result.is_ok());
+        assert_eq!(saved.path, path);
+
+        respond(&mut app, [&path]);
+        let task = app
+            .world_mut()
+            .commands()
+            .dialog()
+            .load_file_async::<Notes>();
+        let DialogOutcome::Loaded(loaded) = complete(&mut app, task) else {
+            panic!("file not loaded");
+        };
+        assert_eq!(loaded.file_name, "notes.txt");
+        assert_eq!(*loaded.contents, b"notes");
+    }
+
+    #[test]
+    fn load_async_too_large_and_failed() {
+        let directory = test_directory("task_load_errors");
+        let image = directory.join("image.png");
+        fs::write(&image, b"not an image").unwrap();
+        let mut app = testing::app(FileDialogPlugin::new());
+
+        respond(&mut app, [&image]);
+        let task = app
+            .world_mut()
+            .commands()
+            .dialog()
+            .set_max_file_size(4)
+            .load_file_async::<Notes>();
+        let DialogOutcome::TooLarge(too_large) = complete(&mut app, task) else {
+            panic!("file not too large");
+        };
+        assert_eq!((too_large.size, too_large.max_size), (12, 4));
+
+        respond(&mut app, [&image]);
+        let task = app
+            .world_mut()
+            .commands()
+            .dialog()
+            .set_verify_content(true)
+            .load_file_async::<Notes>();
+        let DialogOutcome::Failed(failed) = complete(&mut app, task) else {
+            panic!("file not failed");
+        };
+        assert_eq!(failed.file_name, "image.png");
+    }
+
+    #[test]
+    fn pick_paths_async() {
+        let directory = test_directory("task_pick");
+        let file = directory.join("notes.txt");
+        fs::write(&file, b"notes").unwrap();
+        let mut app = testing::app(FileDialogPlugin::new());
+
+        respond(&mut app, [&file]);
+        let task = app
+            .world_mut()
+            .commands()
+            .dialog()
+            .pick_file_path_async::<Notes>();
+        let DialogOutcome::Picked(picked) = complete(&mut app, task) else {
+            panic!("file not picked");
+        };
+        assert_eq!(picked.path, file);
+
+        respond(&mut app, [&directory]);
+        let task = app
+            .world_mut()
+            .commands()
+            .dialog()
+            .pick_directory_path_async::<Notes>();
+        let DialogOutcome::DirectoryPicked(picked) = complete(&mut app, task) else {
+            panic!("directory not picked");
+        };
+        assert_eq!(picked.path, directory);
+    }
+
+    #[test]
+    fn canceled_async() {
+        let mut app = testing::app(FileDialogPlugin::new());
+        for _ in 0..4 {
+            respond(&mut app, Vec::<&str>::new());
+        }
+
+        let mut commands = app.world_mut().commands();
+        let save = commands
+            .dialog()
+            .save_file_async::<Notes>(b"notes".to_vec());
+        let load = commands.dialog().load_file_async::<Notes>();
+        let pick_file = commands.dialog().pick_file_path_async::<Notes>();
+        let pick_directory = commands.dialog().pick_directory_path_async::<Notes>();
+
+        assert!(matches!(complete(&mut app, save), DialogOutcome::Canceled));
+        assert!(matches!(block_on(load), DialogOutcome::Canceled));
+        assert!(matches!(block_on(pick_file), DialogOutcome::Canceled));
+        assert!(matches!(block_on(pick_directory), DialogOutcome::Canceled));
+    }
+
+    #[test]
+    fn canceled_when_not_opened() {
+        testing::init_task_pools();
+
+        let world = World::new();
+        let mut queue = CommandQueue::default();
+        let task = Commands::new(&mut queue, &world)
+            .dialog()
+            .load_file_async::<Notes>();
+        drop(queue);
+
+        assert!(matches!(block_on(task), DialogOutcome::Canceled));
+    }
+
+    #[test]
+    fn async_dialog_round_trip() {
+        let path = test_directory("task_async_dialog").join("notes.txt");
+        let pick = || Dialog::Mock(vec![path.clone()]);
+
+        let saved = block_on(AsyncDialog::new().save_file_with::<Notes>(pick(), b"notes".to_vec()));
+        let DialogOutcome::Saved(saved) = saved else {
+            panic!("file not saved");
+        };
+        assert!(saved.result.is_ok());
+        assert_eq!(fs::read(&path).unwrap(), b"notes");
+
+        let loaded = block_on(AsyncDialog::new().load_file_with::<Notes>(pick()));
+        let DialogOutcome::Loaded(loaded) = loaded else {
+            panic!("file not loaded");
+        };
+        assert_eq!(*loaded.contents, b"notes");
+
+        let too_large = block_on(
+            AsyncDialog::new()
+                .set_max_file_size(2)
+                .load_file_with::<Notes>(pick()),
+        );
+        assert!(matches!(too_large, DialogOutcome::TooLarge(_)));
+
+        let canceled = block_on(AsyncDialog::new().load_file_with::<Notes>(Dialog::Mock(vec![])));
+        assert!(matches!(canceled, DialogOutcome::Canceled));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn async_dialog_compressed_round_trip() {
+        let path = test_directory("task_async_dialog_gzip").join("notes.txt.gz");
+        let pick = || Dialog::Mock(vec![path.clone()]);
+        let dialog = || AsyncDialog::new().set_compression(Compression::Gzip);
+
+        block_on(dialog().save_file_with::<Notes>(pick(), b"notes".to_vec()));
+        assert!(fs::read(&path).unwrap().starts_with(&[0x1f, 0x8b]));
+
+        let DialogOutcome::Loaded(loaded) = block_on(dialog().load_file_with::<Notes>(pick()))
+        else {
+            panic!("file not loaded");
+        };
+        assert_eq!(*loaded.contents, b"notes");
+    }
+}
@@ -0,0 +1,103 @@
+//! Helpers for tests that open dialogs answered by [`MockDialogBackend`].
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use bevy_app::prelude::*;
+use bevy_ecs::message::Messages;
+use bevy_ecs::prelude::*;
+use bevy_tasks::{AsyncComputeTaskPool, IoTaskPool, TaskPool};
+
+use crate::{FileDialogPlugin, MockDialogBackend};
+
+/// Initialize task pools that dialogs run on.
+pub(crate) fn init_task_pools() {
+    AsyncComputeTaskPool::get_or_init(TaskPool::new);
+    IoTaskPool::get_or_init(TaskPool::new);
+}
+
+/// Create app with `plugin` and [`MockDialogBackend`].
+pub(crate) fn app(plugin: FileDialogPlugin) -> App {
+    init_task_pools();
+
+    let mut app = App::new();
+    app.add_plugins(plugin);
+    app.init_resource::<MockDialogBackend>();
+    app
+}
+
+/// Answer the next dialog of `app` with `paths`, cancel it when there are
+/// none.
+pub(crate) fn respond<P: Into<PathBuf>>(app: &mut App, paths: impl IntoIterator<Item = P>) {
+    let paths: Vec<PathBuf> = paths.into_iter().map(Into::into).collect();
+    let mut backend = app.world_mut().resource_mut::<MockDialogBackend>();
+    if paths.is_empty() {
+        backend.push_cancel();
+    } else {
+        backend.push_pick_multiple(paths);
+    }
+}
+
+/// Number of times dialog tasks woke up the app, counted instead of waking
+/// up winit when the resource exists.
+#[derive(Resource, Default)]
+pub(crate) struct WakeUps(pub(crate) Arc<AtomicUsize>);
+
+impl WakeUps {
+    pub(crate) fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Messages `M` taken by [`collect`].
+#[derive(Resource)]
+pub(crate) struct Received<M>(pub(crate) Vec<M>);
+
+/// Take all messages `M` sent in `app` into [`Received<M>`].
+pub(crate) fn collect<M: Message>(app: &mut App) {
+    app.insert_resource(Received::<M>(Vec::new()));
+    app.add_systems(
+        Last,
+        |mut messages: ResMut<Messages<M>>, mut received: ResMut<Received<M>>| {
+            received.0.extend(messages.drain());
+        },
+    );
+}
+
+/// Messages `M` received so far, requires [`collect`].
+pub(crate) fn received<M: Message>(app: &App) -> &[M] {
+    &app.world().resource::<Received<M>>().0
+}
+
+/// Update `app` until `done` returns true, and once more for messages that
+/// were sent together, but received by systems that already ran.
+pub(crate) fn update_until(app: &mut App, done: impl Fn(&World) -> bool) {
+    for _ in 0..200 {
+        app.update();
+        if done(app.world()) {
+            app.update();
+            return;
+        }
+
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    panic!("app did not reach expected state");
+}
+
+/// Update `app` until at least one message `M` is received, requires
+/// [`collect`].
+pub(crate) fn update_until_received<M: Message>(app: &mut App) {
+    update_until(app, |world| !world.resource::<Received<M>>().0.is_empty());
+}
+
+/// Create empty directory for the test, removing what previous run left.
+pub(crate) fn test_directory(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("bevy_file_dialog_{name}"));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    directory
+}
@@ -0,0 +1,414 @@
+use std::marker::PhantomData;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_tasks::prelude::*;
+use crossbeam_channel::bounded;
+
+use crate::{
+    handle_dialog_result, read_limited, write_encoded, AppWaker, DialogFileLoadCanceled,
+    DialogFileLoadFailed, DialogFileSaved, DialogFileTooLarge, DialogResult, FileDialog,
+    LoadContents, MaxFileSize, SaveContents, StreamReceiver, StreamSender, WakeUpOnDrop,
+};
+
+/// Text encoding of loaded or saved text file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextEncoding {
+    /// UTF-8 without byte order mark.
+    #[default]
+    Utf8,
+
+    /// UTF-8 with byte order mark.
+    Utf8Bom,
+
+    /// UTF-16 little endian with byte order mark.
+    Utf16Le,
+
+    /// UTF-16 big endian with byte order mark.
+    Utf16Be,
+
+    /// ISO-8859-1, used when file has no byte order mark and is not valid
+    /// UTF-8. Characters that can't be represented are saved as `?`.
+    Latin1,
+}
+
+/// Line ending of loaded or saved text file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`, used on Linux and macOS.
+    #[default]
+    Lf,
+
+    /// `\r\n`, used on Windows.
+    CrLf,
+
+    /// `\r`, used on classic Mac OS.
+    Cr,
+}
+
+impl LineEnding {
+    fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Cr => "\r",
+        }
+    }
+}
+
+/// Encoding and line ending used by [`FileDialog::save_text`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TextFormat {
+    /// Encoding of the saved file.
+    pub encoding: TextEncoding,
+
+    /// Line ending of the saved file.
+    pub line_ending: LineEnding,
+}
+
+/// Event that gets sent when text file gets loaded with
+/// [`FileDialog::load_text`].
+#[derive(Message)]
+pub struct DialogTextLoaded<T: LoadContents> {
+    /// Name of loaded file.
+    pub file_name: String,
+
+    /// Decoded text of loaded file, all line endings are converted to `\n`.
+    pub text: String,
+
+    /// Detected encoding of loaded file.
+    pub encoding: TextEncoding,
+
+    /// Most common line ending in loaded file, `None` if the file is a
+    /// single line.
+    pub line_ending: Option<LineEnding>,
+
+    /// Path to loaded file.
+    ///
+    /// Does not exist in wasm, you can use this on native platforms only.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub path: std::path::PathBuf,
+
+    marker: PhantomData<T>,
+}
+
+impl<T: LoadContents> DialogTextLoaded<T> {
+    /// Encoding and line ending of loaded file, pass it to
+    /// [`FileDialog::save_text`] to save the text back in the same format.
+    pub fn format(&self) -> TextFormat {
+        TextFormat {
+            encoding: self.encoding,
+            line_ending: self.line_ending.unwrap_or_default(),
+        }
+    }
+}
+
+pub(crate) fn register_load_text<T: LoadContents>(app: &mut App) {
+    let (tx, rx) = bounded::<DialogResult<DialogTextLoaded<T>>>(1);
+    app.insert_resource(StreamSender(tx));
+    app.insert_resource(StreamReceiver(rx));
+    app.add_message::<DialogTextLoaded<T>>();
+    app.add_systems(
+        First,
+        handle_dialog_result::<DialogTextLoaded<T>, DialogFileLoadCanceled<T>>,
+    );
+}
+
+impl FileDialog<'_, '_, '_> {
+    /// Open pick file dialog and load its contents as text. When file gets
+    /// loaded, the [`DialogTextLoaded<T>`] gets sent. You can read this event
+    /// with Bevy's [`EventReader<DialogTextLoaded<T>>`].
+    ///
+    /// Encoding is detected from byte order mark, files without it are
+    /// decoded as UTF-8 when valid, otherwise as Latin-1.
+    pub fn load_text<T: LoadContents>(self) {
+        self.commands.queue(move |world: &mut World| {
+            let sender = world
+                .get_resource::<StreamSender<DialogResult<DialogTextLoaded<T>>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
+
+            let too_large_sender = world
+                .get_resource::<StreamSender<DialogFileTooLarge<T>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
+
+            let failed_sender = world
+                .get_resource::<StreamSender<DialogFileLoadFailed<T>>>()
+                .expect("FileDialogPlugin not initialized with 'with_load_file::<T>()'")
+                .0
+                .clone();
+
+            let max_size = self.load_options.max_size.or_else(|| {
+                world
+                    .get_resource::<MaxFileSize<T>>()
+                    .map(|max_size| max_size.0)
+            });
+
+            let waker = AppWaker::from_world(world);
+
+            let codec = self.codec.with_format::<T>(world);
+
+            let dialog = self.config.apply::<T>(self.dialog, world);
+
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    let file = dialog.pick_file().await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
+
+                    let Some(file) = file else {
+                        sender.send(DialogResult::Canceled).unwrap();
+                        return;
+                    };
+
+                    let contents = match read_limited(&file, max_size).await {
+                        Ok(contents) => contents,
+                        Err(event) => {
+                            too_large_sender.send(event).unwrap();
+                            return;
+                        }
+                    };
+
+                    let contents = match codec.decode(&file, contents, max_size) {
+                        Ok(Ok(contents)) => contents,
+                        Ok(Err(size)) => {
+                            let max_size = max_size.unwrap_or_default();
+                            let event = DialogFileTooLarge::new(&file, size, max_size);
+                            too_large_sender.send(event).unwrap();
+                            return;
+                        }
+                        Err(error) => {
+                            let event = DialogFileLoadFailed::new(&file, error);
+                            failed_sender.send(event).unwrap();
+                            return;
+                        }
+                    };
+
+                    let (text, encoding) = decode_text(&contents);
+                    let (text, line_ending) = normalize_line_endings(&text);
+
+                    let event = DialogTextLoaded {
+                        file_name: file.file_name(),
+                        text,
+                        encoding,
+                        line_ending,
+                        #[cfg(not(target_arch = "wasm32"))]
+                        path: file.path().to_path_buf(),
+                        marker: PhantomData,
+                    };
+
+                    sender.send(DialogResult::Single(event)).unwrap();
+                })
+                .detach();
+        });
+    }
+
+    /// Open save file dialog and save `text` to that file with `format`. All
+    /// line endings in `text` are converted to [`TextFormat::line_ending`].
+    /// When file gets saved, the [`DialogFileSaved<T>`] gets sent.
+    pub fn save_text<T: SaveContents>(self, text: impl Into<String>, format: TextFormat) {
+        let text = text.into();
+
+        self.commands.queue(move |world: &mut World| {
+            let sender = world
+                .get_resource::<StreamSender<DialogResult<DialogFileSaved<T>>>>()
+                .expect("FileDialogPlugin not initialized with 'with_save_file::<T>()'")
+                .0
+                .clone();
+
+            let waker = AppWaker::from_world(world);
+
+            let codec = self.codec.with_format::<T>(world);
+
+            let dialog = self.config.apply::<T>(self.dialog, world);
+
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    let file = dialog.save_file().await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
+
+                    let Some(file) = file else {
+                        sender.send(DialogResult::Canceled).unwrap();
+                        return;
+                    };
+
+                    let (text, _) = normalize_line_endings(&text);
+                    let text = text.replace('\n', format.line_ending.as_str());
+                    let contents = encode_text(&text, format.encoding);
+
+                    let event = DialogFileSaved {
+                        file_name: file.file_name(),
+                        result: write_encoded(&file, &codec, contents).await,
+                        #[cfg(not(target_arch = "wasm32"))]
+                        path: file.path().to_path_buf(),
+                        marker: PhantomData,
+                    };
+
+                    sender.send(DialogResult::Single(event)).unwrap();
+                })
+                .detach();
+        });
+    }
+}
+
+fn decode_text(bytes: &[u8]) -> (String, TextEncoding) {
+    if let Some(bytes) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return (
+            String::from_utf8_lossy(bytes).into_owned(),
+            TextEncoding::Utf8Bom,
+        );
+    }
+
+    if let Some(bytes) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        return (
+            decode_utf16(bytes, u16::from_le_bytes),
+            TextEncoding::Utf16Le,
+        );
+    }
+
+    if let Some(bytes) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        return (
+            decode_utf16(bytes, u16::from_be_bytes),
+            TextEncoding::Utf16Be,
+        );
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), TextEncoding::Utf8),
+        Err(_) => (
+            bytes.iter().map(|&byte| byte as char).collect(),
+            TextEncoding::Latin1,
+        ),
+    }
+}
+
+/// Decode UTF-16 `bytes` with `unit` byte order, invalid code units and odd
+/// trailing byte are replaced with `U+FFFD`.
+fn decode_utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> String {
+    let chunks = bytes.chunks_exact(2);
+    let truncated = !chunks.remainder().is_empty();
+
+    let units: Vec<_> = chunks.map(|chunk| unit([chunk[0], chunk[1]])).collect();
+    let mut text = String::from_utf16_lossy(&units);
+    if truncated {
+        text.push(char::REPLACEMENT_CHARACTER);
+    }
+
+    text
+}
+
+fn encode_text(text: &str, encoding: TextEncoding) -> Vec<u8> {
+    match encoding {
+        TextEncoding::Utf8 => text.as_bytes().to_vec(),
+        TextEncoding::Utf8Bom => [&[0xEF, 0xBB, 0xBF], text.as_bytes()].concat(),
+        TextEncoding::Utf16Le => [0xFF, 0xFE]
+            .into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect(),
+        TextEncoding::Utf16Be => [0xFE, 0xFF]
+            .into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+            .collect(),
+        TextEncoding::Latin1 => text
+            .chars()
+            .map(|char| u8::try_from(char).unwrap_or(b'?'))
+            .collect(),
+    }
+}
+
+/// Convert all line endings in `text` to `\n` and return the most common
+/// original line ending.
+fn normalize_line_endings(text: &str) -> (String, Option<LineEnding>) {
+    let mut normalized = String::with_capacity(text.len());
+    let (mut lf, mut crlf, mut cr) = (0, 0, 0);
+
+    let mut chars = text.chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '\r' if chars.peek() == Some(&'\n') => {
+                chars.next();
+                crlf += 1;
+                normalized.push('\n');
+            }
+            '\r' => {
+                cr += 1;
+                normalized.push('\n');
+            }
+            '\n' => {
+                lf += 1;
+                normalized.push('\n');
+            }
+            char => normalized.push(char),
+        }
+    }
+
+    let line_ending = [
+        (lf, LineEnding::Lf),
+        (crlf, LineEnding::CrLf),
+        (cr, LineEnding::Cr),
+    ]
+    .into_iter()
+    .filter(|&(count, _)| count > 0)
+    .max_by_key(|&(count, _)| count)
+    .map(|(_, line_ending)| line_ending);
+
+    (normalized, line_ending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let text = "Příliš žluťoučký kůň\n🐎";
+
+        for encoding in [
+            TextEncoding::Utf8,
+            TextEncoding::Utf8Bom,
+            TextEncoding::Utf16Le,
+            TextEncoding::Utf16Be,
+        ] {
+            let bytes = encode_text(text, encoding);
+            assert_eq!(decode_text(&bytes), (text.to_string(), encoding));
+        }
+    }
+
+    #[test]
+    fn latin1() {
+        let bytes = encode_text("café", TextEncoding::Latin1);
+        assert_eq!(bytes, b"caf\xE9");
+        assert_eq!(
+            decode_text(&bytes),
+            ("café".to_string(), TextEncoding::Latin1)
+        );
+
+        assert_eq!(encode_text("kůň", TextEncoding::Latin1), b"k??");
+    }
+
+    #[test]
+    fn utf16_odd_length() {
+        let (text, encoding) = decode_text(&[0xFF, 0xFE, b'a', 0, b'b']);
+        assert_eq!(text, "a\u{FFFD}");
+        assert_eq!(encoding, TextEncoding::Utf16Le);
+
+        let (text, encoding) = decode_text(&[0xFE, 0xFF, 0, b'a', 0]);
+        assert_eq!(text, "a\u{FFFD}");
+        assert_eq!(encoding, TextEncoding::Utf16Be);
+    }
+
+    #[test]
+    fn line_endings() {
+        assert_eq!(
+            normalize_line_endings("a\r\nb\r\nc\nd"),
+            ("a\nb\nc\nd".to_string(), Some(LineEnding::CrLf))
+        );
+        assert_eq!(
+            normalize_line_endings("a\rb"),
+            ("a\nb".to_string(), Some(LineEnding::Cr))
+        );
+        assert_eq!(normalize_line_endings("a"), ("a".to_string(), None));
+    }
+}
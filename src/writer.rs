@@ -0,0 +1,258 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_tasks::prelude::*;
+use crossbeam_channel::{bounded, Sender};
+
+use crate::{
+    handle_message, AppWaker, DialogFileSaved, DialogResult, FileDialog, SaveContents,
+    StreamReceiver, StreamSender, WakeUpOnDrop,
+};
+
+/// Number of bytes written between two progress events.
+const PROGRESS_STEP: u64 = 1024 * 1024;
+
+/// Event that gets sent periodically while file contents are being written
+/// with [`FileDialog::save_file_with_writer`].
+///
+/// Progress events may be coalesced when they are produced faster than they
+/// are consumed, only [`DialogFileSaved<T>`] is guaranteed to arrive.
+#[derive(Message)]
+pub struct DialogFileSaveProgress<T: SaveContents> {
+    /// Name of file being saved.
+    pub file_name: String,
+
+    /// Path to file being saved.
+    pub path: PathBuf,
+
+    /// Number of bytes written so far.
+    pub written: u64,
+
+    marker: PhantomData<T>,
+}
+
+pub(crate) fn register_save_progress<T: SaveContents>(app: &mut App) {
+    let (tx, rx) = bounded::<DialogFileSaveProgress<T>>(1);
+    app.insert_resource(StreamSender(tx));
+    app.insert_resource(StreamReceiver(rx));
+    app.add_message::<DialogFileSaveProgress<T>>();
+    app.add_systems(First, handle_message::<DialogFileSaveProgress<T>>);
+}
+
+impl FileDialog<'_, '_, '_> {
+    /// Open save file dialog and let `producer` write the contents directly
+    /// to the picked file. The `producer` runs on [`AsyncComputeTaskPool`]
+    /// only after the user picks the file, so the contents never have to be
+    /// in memory all at once. While the file is being written,
+    /// [`DialogFileSaveProgress<T>`] gets sent, and when `producer` finishes,
+    /// the [`DialogFileSaved<T>`] gets sent with its result.
+    ///
+    /// When the dialog is canceled, `producer` is not called at all. The
+    /// contents are written to temporary file next to the picked file, which
+    /// replaces the picked file only when `producer` succeeds, so failed save
+    /// never leaves partly written file behind.
+    ///
+    /// Written contents are not encoded, [`FileDialog::set_compression`],
+    /// `FileDialog::set_password`, `FileDialog::set_checksum` and
+    /// [`VersionedFormat`](crate::VersionedFormat) don't apply to this
+    /// function.
+    ///
+    /// Does not exist in `wasm32`.
+    pub fn save_file_with_writer<T: SaveContents>(
+        self,
+        producer: impl FnOnce(&mut dyn Write) -> io::Result<()> + Send + 'static,
+    ) {
+        self.commands.queue(move |world: &mut World| {
+            let sender = world
+                .get_resource::<StreamSender<DialogResult<DialogFileSaved<T>>>>()
+                .expect("FileDialogPlugin not initialized with 'with_save_file::<T>()'")
+                .0
+                .clone();
+
+            let progress_sender = world
+                .get_resource::<StreamSender<DialogFileSaveProgress<T>>>()
+                .expect("FileDialogPlugin not initialized with 'with_save_file::<T>()'")
+                .0
+                .clone();
+
+            let waker = AppWaker::from_world(world);
+
+            let dialog = self.config.apply::<T>(self.dialog, world);
+
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    let file = dialog.save_file().await;
+                    let _wake_up = waker.as_ref().map(WakeUpOnDrop);
+
+                    let Some(file) = file else {
+                        sender.send(DialogResult::Canceled).unwrap();
+                        return;
+                    };
+
+                    let file_name = file.file_name();
+                    let path = file.path().to_path_buf();
+
+                    let partial = partial_path(&path);
+                    let result = File::create(&partial)
+                        .and_then(|output| {
+                            let mut writer = ProgressWriter {
+                                inner: BufWriter::new(output),
+                                written: 0,
+                                reported: 0,
+                                file_name: &file_name,
+                                path: &path,
+                                sender: &progress_sender,
+                            };
+
+                            producer(&mut writer)?;
+                            writer.flush()
+                        })
+                        .and_then(|()| fs::rename(&partial, &path));
+
+                    if result.is_err() {
+                        // The file may not exist when it could not be created
+                        let _ = fs::remove_file(&partial);
+                    }
+
+                    let event = DialogFileSaved {
+                        file_name,
+                        result,
+                        path,
+                        marker: PhantomData,
+                    };
+
+                    sender.send(DialogResult::Single(event)).unwrap();
+                })
+                .detach();
+        });
+    }
+}
+
+/// Path of temporary file next to `path` that the contents get written to
+/// before they replace `path`.
+fn partial_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".part");
+    path.with_file_name(file_name)
+}
+
+/// Writer that counts written bytes and reports progress.
+struct ProgressWriter<'a, W: Write, T: SaveContents> {
+    inner: W,
+    written: u64,
+    reported: u64,
+    file_name: &'a str,
+    path: &'a PathBuf,
+    sender: &'a Sender<DialogFileSaveProgress<T>>,
+}
+
+impl<W: Write, T: SaveContents> ProgressWriter<'_, W, T> {
+    fn report(&mut self) {
+        self.reported = self.written;
+
+        // Intermediate progress is dropped when the previous one was not
+        // consumed yet, so writing is never blocked by the app.
+        let _ = self.sender.try_send(DialogFileSaveProgress {
+            file_name: self.file_name.to_string(),
+            path: self.path.clone(),
+            written: self.written,
+            marker: PhantomData,
+        });
+    }
+}
+
+impl<W: Write, T: SaveContents> Write for ProgressWriter<'_, W, T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.written += written as u64;
+
+        if self.written - self.reported >= PROGRESS_STEP {
+            self.report();
+        }
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()?;
+
+        if self.written != self.reported {
+            self.report();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use crate::{FileDialogExt, FileDialogPlugin};
+
+    struct Export;
+
+    fn save(
+        name: &str,
+        producer: impl FnOnce(&mut dyn Write) -> io::Result<()> + Send + 'static,
+    ) -> (App, PathBuf) {
+        let path = testing::test_directory(name).join("export.bin");
+
+        let mut app = testing::app(FileDialogPlugin::new().with_save_file::<Export>());
+        testing::collect::<DialogFileSaved<Export>>(&mut app);
+        testing::collect::<DialogFileSaveProgress<Export>>(&mut app);
+        testing::respond(&mut app, [&path]);
+
+        app.world_mut()
+            .commands()
+            .dialog()
+            .save_file_with_writer::<Export>(producer);
+        testing::update_until_received::<DialogFileSaved<Export>>(&mut app);
+
+        (app, path)
+    }
+
+    #[test]
+    fn write_file() {
+        let (app, path) = save("writer_success", |writer| writer.write_all(b"exported"));
+
+        let saved = &testing::received::<DialogFileSaved<Export>>(&app)[0];
+        assert!(saved.result.is_ok());
+        assert_eq!(fs::read(&path).unwrap(), b"exported");
+        assert!(!partial_path(&path).exists());
+    }
+
+    #[test]
+    fn producer_error_leaves_no_file() {
+        let (app, path) = save("writer_error", |writer| {
+            writer.write_all(b"partial")?;
+            Err(io::Error::other("producer failed"))
+        });
+
+        let saved = &testing::received::<DialogFileSaved<Export>>(&app)[0];
+        assert!(saved.result.is_err());
+        assert!(!path.exists());
+        assert!(!partial_path(&path).exists());
+    }
+
+    #[test]
+    fn progress_reported() {
+        let size = PROGRESS_STEP as usize * 2 + 10;
+        let (app, _) = save("writer_progress", move |writer| {
+            writer.write_all(&vec![0; size])
+        });
+
+        // Progress that was not consumed yet may be dropped
+        let written: Vec<_> = testing::received::<DialogFileSaveProgress<Export>>(&app)
+            .iter()
+            .map(|event| event.written)
+            .collect();
+        assert!(!written.is_empty());
+        assert!(written.is_sorted());
+        assert!(written.iter().all(|written| *written <= size as u64));
+    }
+}